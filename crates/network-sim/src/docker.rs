@@ -3,8 +3,11 @@
 //! This module provides utilities for creating and managing network
 //! namespaces and interfaces within Docker containers for testing.
 
-use std::process::Command;
+use crate::qdisc::{NetemConfig, QdiscError, QdiscManager, QdiscState};
+use crate::types::NetworkParams;
+use log::{debug, info};
 use thiserror::Error;
+use tokio::process::Command;
 
 #[derive(Error, Debug)]
 pub enum DockerNetworkError {
@@ -16,6 +19,119 @@ pub enum DockerNetworkError {
 
     #[error("Interface configuration failed: {0}")]
     InterfaceError(String),
+
+    #[error("Qdisc error: {0}")]
+    Qdisc(#[from] QdiscError),
+}
+
+/// A container link shaped on its host-side veth peer
+#[derive(Debug, Clone)]
+pub struct ContainerLink {
+    pub container_id: String,
+    /// Host end of the container's veth pair (egress = traffic towards the container)
+    pub host_interface: String,
+    pub params: NetworkParams,
+}
+
+impl ContainerLink {
+    /// Read back qdisc state and counters from the host interface
+    pub async fn stats(
+        &self,
+        qdisc_manager: &QdiscManager,
+    ) -> Result<QdiscState, DockerNetworkError> {
+        Ok(qdisc_manager.get_qdisc_state(&self.host_interface).await?)
+    }
+
+    /// Remove the shaping qdisc; the container and its veth are left alone
+    pub async fn cleanup(&self, qdisc_manager: &QdiscManager) -> Result<(), DockerNetworkError> {
+        qdisc_manager.clear_interface(&self.host_interface).await?;
+        info!(
+            "Removed shaping from container {} ({})",
+            self.container_id, self.host_interface
+        );
+        Ok(())
+    }
+}
+
+/// Shape a running container's network link from the host.
+///
+/// Looks up the peer ifindex of the container's `eth0`, finds the matching veth
+/// on the host bridge and applies the netem chain there. No namespaces need to
+/// be created, so real receiver containers can be impaired in CI.
+pub async fn shape_container_link(
+    qdisc_manager: &QdiscManager,
+    container_id: &str,
+    params: &NetworkParams,
+) -> Result<ContainerLink, DockerNetworkError> {
+    let host_interface = container_host_interface(container_id).await?;
+    debug!(
+        "Container {} eth0 peer is host interface {}",
+        container_id, host_interface
+    );
+
+    qdisc_manager
+        .configure_interface(&host_interface, NetemConfig::from(params))
+        .await?;
+    info!(
+        "Shaped container {} via {}: {}ms delay, {}% loss, {} kbps",
+        container_id,
+        host_interface,
+        params.delay_ms,
+        params.loss_pct * 100.0,
+        params.rate_kbps
+    );
+
+    Ok(ContainerLink {
+        container_id: container_id.to_string(),
+        host_interface,
+        params: params.clone(),
+    })
+}
+
+/// Find the host-side veth peer of a container's `eth0`
+pub async fn container_host_interface(container_id: &str) -> Result<String, DockerNetworkError> {
+    let output = Command::new("docker")
+        .args(["exec", container_id, "cat", "/sys/class/net/eth0/iflink"])
+        .output()
+        .await
+        .map_err(|e| DockerNetworkError::CommandFailed(format!("Failed to run docker: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DockerNetworkError::CommandFailed(format!(
+            "Failed to read eth0 iflink in container {}: {}",
+            container_id, stderr
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let ifindex: u32 = stdout.trim().parse().map_err(|_| {
+        DockerNetworkError::InterfaceError(format!(
+            "Unexpected iflink for container {}: {:?}",
+            container_id,
+            stdout.trim()
+        ))
+    })?;
+
+    host_interface_by_index(ifindex)?.ok_or_else(|| {
+        DockerNetworkError::InterfaceError(format!(
+            "No host interface with index {} for container {}",
+            ifindex, container_id
+        ))
+    })
+}
+
+fn host_interface_by_index(ifindex: u32) -> Result<Option<String>, DockerNetworkError> {
+    let entries = std::fs::read_dir("/sys/class/net").map_err(|e| {
+        DockerNetworkError::CommandFailed(format!("Failed to list /sys/class/net: {}", e))
+    })?;
+    for entry in entries.flatten() {
+        let index = std::fs::read_to_string(entry.path().join("ifindex")).unwrap_or_default();
+        if index.trim().parse::<u32>().ok() == Some(ifindex) {
+            return Ok(Some(entry.file_name().to_string_lossy().into_owned()));
+        }
+    }
+    Ok(None)
 }
 
 /// Docker-compatible network test environment
@@ -83,6 +199,7 @@ impl DockerNetworkEnv {
         let output = Command::new("ip")
             .args(["netns", "add", name])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!(
                     "Failed to create namespace {}: {}",
@@ -114,6 +231,7 @@ impl DockerNetworkEnv {
         let output = Command::new("ip")
             .args(["link", "add", if1, "type", "veth", "peer", "name", if2])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!("Failed to create veth pair: {}", e))
             })?;
@@ -143,6 +261,7 @@ impl DockerNetworkEnv {
         let output = Command::new("ip")
             .args(["link", "set", interface, "netns", namespace])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!("Failed to move interface: {}", e))
             })?;
@@ -167,6 +286,7 @@ impl DockerNetworkEnv {
         let output = Command::new("ip")
             .args(["addr", "add", addr, "dev", interface])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!("Failed to configure interface: {}", e))
             })?;
@@ -197,6 +317,7 @@ impl DockerNetworkEnv {
                 "netns", "exec", namespace, "ip", "addr", "add", addr, "dev", interface,
             ])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!(
                     "Failed to configure interface in namespace: {}",
@@ -223,6 +344,7 @@ impl DockerNetworkEnv {
         let output = Command::new("ip")
             .args(["link", "set", interface, "up"])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!("Failed to bring up interface: {}", e))
             })?;
@@ -249,6 +371,7 @@ impl DockerNetworkEnv {
                 "netns", "exec", namespace, "ip", "link", "set", interface, "up",
             ])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!(
                     "Failed to bring up interface in namespace: {}",
@@ -278,6 +401,7 @@ impl DockerNetworkEnv {
                 "netns", "exec", from_ns, "ping", "-c", "1", "-W", "1", to_ip,
             ])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!("Failed to test connectivity: {}", e))
             })?;
@@ -296,7 +420,8 @@ impl DockerNetworkEnv {
         // Delete existing qdisc (ignore errors)
         let _ = Command::new("tc")
             .args(["qdisc", "del", "dev", interface, "root"])
-            .output();
+            .output()
+            .await;
 
         // Add netem qdisc with impairments
         let loss_str = format!("{}%", loss_pct);
@@ -309,6 +434,7 @@ impl DockerNetworkEnv {
                 &delay_str, "loss", &loss_str, "rate", &rate_str,
             ])
             .output()
+            .await
             .map_err(|e| {
                 DockerNetworkError::CommandFailed(format!(
                     "Failed to apply network impairments: {}",
@@ -331,12 +457,15 @@ impl DockerNetworkEnv {
     pub async fn cleanup(&mut self) -> Result<(), DockerNetworkError> {
         // Delete namespaces (this also cleans up interfaces in them)
         for ns in &self.namespaces {
-            let _ = Command::new("ip").args(["netns", "del", ns]).output();
+            let _ = Command::new("ip").args(["netns", "del", ns]).output().await;
         }
 
         // Delete interfaces in root namespace
         for interface in &self.interfaces {
-            let _ = Command::new("ip").args(["link", "del", interface]).output();
+            let _ = Command::new("ip")
+                .args(["link", "del", interface])
+                .output()
+                .await;
         }
 
         self.namespaces.clear();
@@ -348,8 +477,17 @@ impl DockerNetworkEnv {
 
 impl Drop for DockerNetworkEnv {
     fn drop(&mut self) {
-        // Best effort cleanup
-        let _ = futures::executor::block_on(self.cleanup());
+        // Best effort cleanup; synchronous because Drop may run outside a runtime
+        for ns in &self.namespaces {
+            let _ = std::process::Command::new("ip")
+                .args(["netns", "del", ns])
+                .output();
+        }
+        for interface in &self.interfaces {
+            let _ = std::process::Command::new("ip")
+                .args(["link", "del", interface])
+                .output();
+        }
     }
 }
 
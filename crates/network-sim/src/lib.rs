@@ -3,6 +3,7 @@
 //! This library provides utilities for applying fixed network parameters
 //! to network interfaces using Linux network namespaces and qdisc.
 
+pub mod loopback;
 pub mod namespace;
 pub mod qdisc;
 pub mod runtime;
@@ -14,11 +15,16 @@ pub mod docker;
 pub use namespace::{
     cleanup_rist_test_links, cleanup_shaped_veth_pair, create_rist_test_links,
     create_shaped_veth_pair, exec_in_rx_namespace, get_connection_ips, ShapedVethConfig,
+    ShapedVethPairGuard,
 };
+pub use qdisc::{ClassEntry, QdiscCapabilities, QdiscEntry, QdiscState, QdiscStats};
 pub use runtime::{
-    apply_ingress_params, apply_network_params, remove_ingress_params, remove_network_params,
+    apply_htb_params, apply_ingress_params, apply_network_params, apply_network_params_batch,
+    apply_network_params_guarded, apply_network_params_ifindex, capabilities, get_qdisc_state,
+    interface_index, interface_name, remove_ingress_params, remove_network_params,
+    remove_network_params_ifindex, update_network_params, BatchReport, QdiscGuard,
 };
-pub use types::{NetworkParams, RuntimeError};
+pub use types::{HtbClass, HtbConfig, NetworkParams, RuntimeError};
 
 // Expose new APIs (Linux only). Keeping current public API intact.
 #[cfg(target_os = "linux")]
@@ -28,4 +34,8 @@ pub mod nsapi;
 #[cfg(target_os = "linux")]
 pub use link::{VethPair, VethPairConfig};
 #[cfg(target_os = "linux")]
+pub use namespace::{tokio_udp_socket_in, udp_socket_in};
+#[cfg(target_os = "linux")]
 pub use nsapi::{Namespace, NamespaceGuard};
+#[cfg(target_os = "linux")]
+pub use runtime::apply_network_params_in_ns;
@@ -31,6 +31,11 @@ pub struct VethPair {
 
 impl VethPair {
     pub async fn create(qdisc: &QdiscManager, cfg: &VethPairConfig) -> Result<Self> {
+        // Reject bad shaping before touching any links
+        if let Some(params) = &cfg.params {
+            params.validate().map_err(into_io)?;
+        }
+
         // Ensure namespaces
         if let Some(tx) = &cfg.tx_ns {
             let _ = Namespace::ensure(tx.clone()).await?;
@@ -152,26 +157,13 @@ impl VethPair {
 
         // Optional shaping on tx_if in its namespace
         if let Some(params) = &cfg.params {
-            let netem = crate::qdisc::NetemConfig {
-                delay_us: params.delay_ms * 1000,
-                jitter_us: params.jitter_ms * 1000,
-                loss_percent: params.loss_pct * 100.0,
-                loss_correlation: params.loss_corr_pct * 100.0,
-                reorder_percent: params.reorder_pct * 100.0,
-                duplicate_percent: params.duplicate_pct * 100.0,
-                rate_bps: params.rate_kbps as u64 * 1000,
-            };
-            if let Some(ns) = &cfg.tx_ns {
-                qdisc
-                    .configure_interface_in_ns(ns, &cfg.tx_if, netem)
-                    .await
-                    .map_err(|e| into_io(e.into()))?;
-            } else {
-                qdisc
-                    .configure_interface(&cfg.tx_if, netem)
-                    .await
-                    .map_err(|e| into_io(crate::types::RuntimeError::from(e)))?;
+            match &cfg.tx_ns {
+                Some(ns) => {
+                    runtime::apply_network_params_in_ns(qdisc, ns, &cfg.tx_if, params).await
+                }
+                None => runtime::apply_network_params(qdisc, &cfg.tx_if, params).await,
             }
+            .map_err(into_io)?;
         }
 
         Ok(Self {
@@ -0,0 +1,231 @@
+//! Userspace UDP impairment proxy for localhost tests
+//!
+//! Applies the same `NetworkParams` as the qdisc path, but in-process, so
+//! tests that only need "add latency and loss on localhost" can run without
+//! NET_ADMIN. Senders target `ImpairedProxy::port` instead of the real
+//! receiver; datagrams are delayed, dropped, duplicated and rate-limited in
+//! both directions, matching netem on `lo` (RTT = 2 × delay).
+//!
+//! Reordering, loss correlation and port filters are not modelled.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, info};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::types::NetworkParams;
+
+/// A running impairment proxy; aborted on drop
+#[derive(Debug)]
+pub struct ImpairedProxy {
+    /// Localhost port senders should target
+    pub port: u16,
+    upstream: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ImpairedProxy {
+    /// Address senders should target
+    pub fn addr(&self) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], self.port))
+    }
+
+    /// Receiver the proxy forwards to
+    pub fn upstream(&self) -> SocketAddr {
+        self.upstream
+    }
+}
+
+impl Drop for ImpairedProxy {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Start a proxy on localhost that forwards to `upstream` with `params` applied
+pub async fn impair(
+    params: &NetworkParams,
+    upstream: SocketAddr,
+) -> std::io::Result<ImpairedProxy> {
+    params
+        .validate()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+    let front = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let back = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    back.connect(upstream).await?;
+    let port = front.local_addr()?.port();
+
+    // Replies go to whoever sent to the proxy most recently
+    let client: Arc<Mutex<Option<SocketAddr>>> = Arc::new(Mutex::new(None));
+
+    let forward = {
+        let (front, back, client) = (front.clone(), back.clone(), client.clone());
+        let mut shaper = Shaper::new(params.clone(), port as u64);
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 65536];
+            while let Ok((n, from)) = front.recv_from(&mut buf).await {
+                *client.lock().unwrap() = Some(from);
+                for deadline in shaper.schedule(n, Instant::now()) {
+                    let (back, data) = (back.clone(), buf[..n].to_vec());
+                    tokio::spawn(async move {
+                        tokio::time::sleep_until(deadline).await;
+                        let _ = back.send(&data).await;
+                    });
+                }
+            }
+        })
+    };
+
+    let reverse = {
+        let mut shaper = Shaper::new(params.clone(), !(port as u64));
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 65536];
+            while let Ok(n) = back.recv(&mut buf).await {
+                let Some(to) = *client.lock().unwrap() else {
+                    continue;
+                };
+                for deadline in shaper.schedule(n, Instant::now()) {
+                    let (front, data) = (front.clone(), buf[..n].to_vec());
+                    tokio::spawn(async move {
+                        tokio::time::sleep_until(deadline).await;
+                        let _ = front.send_to(&data, to).await;
+                    });
+                }
+            }
+        })
+    };
+
+    info!(
+        "Loopback proxy 127.0.0.1:{} -> {}: {}ms delay, {}% loss, {} kbps",
+        port,
+        upstream,
+        params.delay_ms,
+        params.loss_pct * 100.0,
+        params.rate_kbps
+    );
+
+    Ok(ImpairedProxy {
+        port,
+        upstream,
+        tasks: vec![forward, reverse],
+    })
+}
+
+/// Per-direction impairment state
+struct Shaper {
+    params: NetworkParams,
+    rng: u64,
+    /// When the rate limiter is next idle
+    next_free: Option<Instant>,
+}
+
+impl Shaper {
+    fn new(params: NetworkParams, seed: u64) -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self {
+            params,
+            rng: (nanos ^ seed) | 1,
+            next_free: None,
+        }
+    }
+
+    /// Uniform sample in [0, 1) (xorshift64*)
+    fn next_f32(&mut self) -> f32 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let x = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (x >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Delivery deadlines for a datagram of `len` bytes; empty if dropped
+    fn schedule(&mut self, len: usize, now: Instant) -> Vec<Instant> {
+        if self.params.loss_pct > 0.0 && self.next_f32() < self.params.loss_pct {
+            debug!("Loopback proxy dropped {} byte datagram", len);
+            return Vec::new();
+        }
+
+        // Serialise through the rate limiter like netem's rate option
+        let mut depart = now;
+        if self.params.rate_kbps > 0 {
+            let start = self.next_free.map_or(now, |t| t.max(now));
+            let tx_us = len as u64 * 8 * 1000 / self.params.rate_kbps as u64;
+            depart = start + Duration::from_micros(tx_us);
+            self.next_free = Some(depart);
+        }
+
+        let mut delay_ms = self.params.delay_ms as f32;
+        if self.params.jitter_ms > 0 {
+            delay_ms += (self.next_f32() * 2.0 - 1.0) * self.params.jitter_ms as f32;
+        }
+        let deadline = depart + Duration::from_micros((delay_ms.max(0.0) * 1000.0) as u64);
+
+        let mut deadlines = vec![deadline];
+        if self.params.duplicate_pct > 0.0 && self.next_f32() < self.params.duplicate_pct {
+            deadlines.push(deadline);
+        }
+        deadlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(delay_ms: u32, loss_pct: f32, rate_kbps: u32) -> NetworkParams {
+        NetworkParams {
+            delay_ms,
+            loss_pct,
+            rate_kbps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_shaper_delay_and_loss() {
+        let now = Instant::now();
+
+        let mut shaper = Shaper::new(params(30, 0.0, 0), 1);
+        assert_eq!(
+            shaper.schedule(100, now),
+            vec![now + Duration::from_millis(30)]
+        );
+
+        let mut shaper = Shaper::new(params(30, 1.0, 0), 2);
+        assert!(shaper.schedule(100, now).is_empty());
+    }
+
+    #[test]
+    fn test_shaper_rate_serialises_packets() {
+        let now = Instant::now();
+        // 1000 bytes at 800 kbps = 10 ms on the wire
+        let mut shaper = Shaper::new(params(0, 0.0, 800), 3);
+        let first = shaper.schedule(1000, now)[0];
+        let second = shaper.schedule(1000, now)[0];
+        assert_eq!(first, now + Duration::from_millis(10));
+        assert_eq!(second, now + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_shaper_loss_rate_is_roughly_honoured() {
+        let now = Instant::now();
+        let mut shaper = Shaper::new(params(0, 0.2, 0), 4);
+        let delivered = (0..10_000)
+            .filter(|_| !shaper.schedule(10, now).is_empty())
+            .count();
+        assert!(
+            (7_500..8_500).contains(&delivered),
+            "delivered {}",
+            delivered
+        );
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::qdisc::QdiscManager;
 use crate::types::{NetworkParams, RuntimeError};
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::process::Output;
 use tokio::process::Command;
 
@@ -24,6 +24,117 @@ pub struct ShapedVethConfig {
     pub rx_namespace: Option<String>,
     /// Network parameters to apply to TX interface
     pub network_params: NetworkParams,
+    /// Index of the TX interface; filled in by `create_shaped_veth_pair`
+    pub tx_ifindex: Option<u32>,
+    /// Index of the RX interface within its namespace; filled in on creation
+    pub rx_ifindex: Option<u32>,
+}
+
+/// Owns a shaped veth pair created by `create_shaped_veth_pair`.
+///
+/// On drop the qdisc is removed and the pair (plus its RX namespace) deleted,
+/// so links don't leak when a test panics. Cleanup is synchronous and
+/// best-effort; failures are logged. Use `cleanup` for an explicit async
+/// teardown or `leak` to keep the interfaces.
+#[must_use = "dropping the guard immediately deletes the veth pair"]
+#[derive(Debug)]
+pub struct ShapedVethPairGuard {
+    config: ShapedVethConfig,
+    armed: bool,
+}
+
+impl ShapedVethPairGuard {
+    /// Configuration of the guarded pair
+    pub fn config(&self) -> &ShapedVethConfig {
+        &self.config
+    }
+
+    /// Disarm the guard and keep the interfaces alive
+    pub fn leak(mut self) -> ShapedVethConfig {
+        self.armed = false;
+        self.config.clone()
+    }
+
+    /// Shape the RX end (the reverse direction) inside its namespace
+    pub async fn apply_rx_params(
+        &self,
+        qdisc_manager: &QdiscManager,
+        params: &NetworkParams,
+    ) -> Result<(), RuntimeError> {
+        let rx_ifindex = self.config.rx_ifindex.ok_or_else(|| {
+            RuntimeError::InvalidParams(format!(
+                "no index recorded for {}",
+                self.config.rx_interface
+            ))
+        })?;
+        crate::runtime::apply_network_params_ifindex(
+            qdisc_manager,
+            rx_ifindex,
+            self.config.rx_namespace.as_deref(),
+            params,
+        )
+        .await
+    }
+
+    /// Tear the pair down asynchronously and disarm the guard
+    pub async fn cleanup(mut self, qdisc_manager: &QdiscManager) -> Result<(), RuntimeError> {
+        self.armed = false;
+        cleanup_shaped_veth_pair(qdisc_manager, &self.config).await
+    }
+}
+
+impl std::ops::Deref for ShapedVethPairGuard {
+    type Target = ShapedVethConfig;
+
+    fn deref(&self) -> &ShapedVethConfig {
+        &self.config
+    }
+}
+
+impl Drop for ShapedVethPairGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        debug!(
+            "Guard dropping shaped veth pair: {} <-> {}",
+            self.config.tx_interface, self.config.rx_interface
+        );
+        // Prefer the index in case the interface was renamed
+        let tx_interface = self
+            .config
+            .tx_ifindex
+            .and_then(|index| {
+                let out = std::process::Command::new("ip")
+                    .args(["-o", "link", "show"])
+                    .output()
+                    .ok()?;
+                crate::runtime::parse_links(&String::from_utf8_lossy(&out.stdout))
+                    .into_iter()
+                    .find(|(i, _)| *i == index)
+                    .map(|(_, name)| name)
+            })
+            .unwrap_or_else(|| self.config.tx_interface.clone());
+        let mut cmds: Vec<(&str, Vec<&str>)> =
+            vec![("tc", vec!["qdisc", "del", "dev", &tx_interface, "root"])];
+        if let Some(ref ns) = self.config.rx_namespace {
+            cmds.push(("ip", vec!["netns", "del", ns]));
+        }
+        cmds.push(("ip", vec!["link", "del", "dev", &tx_interface]));
+
+        for (cmd, args) in cmds {
+            match std::process::Command::new(cmd).args(&args).output() {
+                Ok(out) if !out.status.success() => debug!(
+                    "{} {} returned {} (may already be gone)",
+                    cmd,
+                    args.join(" "),
+                    out.status
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("{} {} failed during drop: {}", cmd, args.join(" "), e),
+            }
+        }
+    }
 }
 
 async fn run_ip_cmd(args: &[&str]) -> Result<Output, RuntimeError> {
@@ -95,17 +206,25 @@ async fn add_host_route(
 }
 
 /// Create and configure a shaped veth pair with optional namespace isolation
+///
+/// The returned guard tears the pair down when dropped.
 pub async fn create_shaped_veth_pair(
     qdisc_manager: &QdiscManager,
     config: &ShapedVethConfig,
-) -> Result<(), RuntimeError> {
+) -> Result<ShapedVethPairGuard, RuntimeError> {
     info!(
         "Creating shaped veth pair: {} <-> {}",
         config.tx_interface, config.rx_interface
     );
 
-    // Clean up any existing interfaces/namespaces
-    let _ = cleanup_shaped_veth_pair(qdisc_manager, config).await;
+    // Clean up any existing interfaces/namespaces by name; indices from a
+    // previous run may already belong to other interfaces
+    let stale = ShapedVethConfig {
+        tx_ifindex: None,
+        rx_ifindex: None,
+        ..config.clone()
+    };
+    let _ = cleanup_shaped_veth_pair(qdisc_manager, &stale).await;
 
     // Create veth pair
     run_ip_cmd_check(&[
@@ -120,6 +239,14 @@ pub async fn create_shaped_veth_pair(
     ])
     .await?;
 
+    // From here on, partial setups are removed if a later step fails
+    let mut guard = ShapedVethPairGuard {
+        config: config.clone(),
+        armed: true,
+    };
+    let tx_ifindex = crate::runtime::interface_index(&config.tx_interface, None).await?;
+    guard.config.tx_ifindex = Some(tx_ifindex);
+
     // Configure TX interface in root namespace
     run_ip_cmd_check(&["addr", "add", &config.tx_ip, "dev", &config.tx_interface]).await?;
     run_ip_cmd_check(&["link", "set", "dev", &config.tx_interface, "up"]).await?;
@@ -154,10 +281,14 @@ pub async fn create_shaped_veth_pair(
         ])
         .await?;
         run_ip_cmd_check(&["-n", rx_ns, "link", "set", "dev", "lo", "up"]).await?;
+        guard.config.rx_ifindex =
+            Some(crate::runtime::interface_index(&config.rx_interface, Some(rx_ns)).await?);
     } else {
         // Configure RX interface in root namespace
         run_ip_cmd_check(&["addr", "add", &config.rx_ip, "dev", &config.rx_interface]).await?;
         run_ip_cmd_check(&["link", "set", "dev", &config.rx_interface, "up"]).await?;
+        guard.config.rx_ifindex =
+            Some(crate::runtime::interface_index(&config.rx_interface, None).await?);
     }
 
     // Extract plain IPs (without CIDR) for routing configuration
@@ -197,9 +328,10 @@ pub async fn create_shaped_veth_pair(
     }
 
     // Apply shaping to TX interface
-    crate::runtime::apply_network_params(
+    crate::runtime::apply_network_params_ifindex(
         qdisc_manager,
-        &config.tx_interface,
+        tx_ifindex,
+        None,
         &config.network_params,
     )
     .await?;
@@ -209,7 +341,7 @@ pub async fn create_shaped_veth_pair(
         config.network_params.rate_kbps
     );
 
-    Ok(())
+    Ok(guard)
 }
 
 /// Remove a shaped veth pair and clean up namespaces
@@ -222,8 +354,16 @@ pub async fn cleanup_shaped_veth_pair(
         config.tx_interface, config.rx_interface
     );
 
+    // Resolve by index first so renamed interfaces are still found
+    let tx_interface = match config.tx_ifindex {
+        Some(index) => crate::runtime::interface_name(index, None)
+            .await
+            .unwrap_or_else(|_| config.tx_interface.clone()),
+        None => config.tx_interface.clone(),
+    };
+
     // Remove shaping from TX interface
-    let _ = crate::runtime::remove_network_params(qdisc_manager, &config.tx_interface).await;
+    let _ = crate::runtime::remove_network_params(qdisc_manager, &tx_interface).await;
 
     // Remove namespace if it exists
     if let Some(ref rx_ns) = config.rx_namespace {
@@ -236,13 +376,10 @@ pub async fn cleanup_shaped_veth_pair(
     }
 
     // Remove TX interface (this removes the entire veth pair)
-    let output = run_ip_cmd(&["link", "del", "dev", &config.tx_interface]).await;
+    let output = run_ip_cmd(&["link", "del", "dev", &tx_interface]).await;
     if let Ok(out) = output {
         if !out.status.success() {
-            debug!(
-                "Interface {} may not exist (this is OK)",
-                config.tx_interface
-            );
+            debug!("Interface {} may not exist (this is OK)", tx_interface);
         }
     }
 
@@ -254,8 +391,8 @@ pub async fn create_rist_test_links(
     qdisc_manager: &QdiscManager,
     base_name: &str,
     link_configs: &[(u32, u32)], // (rate_kbps, delay_ms) pairs
-) -> Result<Vec<ShapedVethConfig>, RuntimeError> {
-    let mut configs = Vec::new();
+) -> Result<Vec<ShapedVethPairGuard>, RuntimeError> {
+    let mut links = Vec::new();
 
     for (i, &(rate_kbps, delay_ms)) in link_configs.iter().enumerate() {
         let config = ShapedVethConfig {
@@ -272,29 +409,33 @@ pub async fn create_rist_test_links(
                 reorder_pct: 0.0,
                 duplicate_pct: 0.0,
                 loss_corr_pct: 0.0,
+                port_filter: Vec::new(),
             },
+            tx_ifindex: None,
+            rx_ifindex: None,
         };
 
-        create_shaped_veth_pair(qdisc_manager, &config).await?;
-        configs.push(config);
+        // Earlier links are dropped (and removed) if this one fails
+        links.push(create_shaped_veth_pair(qdisc_manager, &config).await?);
     }
 
     info!(
         "Created {} RIST test links with namespace isolation",
-        configs.len()
+        links.len()
     );
-    Ok(configs)
+    Ok(links)
 }
 
 /// Clean up all RIST test links
 pub async fn cleanup_rist_test_links(
     qdisc_manager: &QdiscManager,
-    configs: &[ShapedVethConfig],
+    links: Vec<ShapedVethPairGuard>,
 ) -> Result<(), RuntimeError> {
-    for config in configs {
-        cleanup_shaped_veth_pair(qdisc_manager, config).await?;
+    let count = links.len();
+    for link in links {
+        link.cleanup(qdisc_manager).await?;
     }
-    info!("Cleaned up {} RIST test links", configs.len());
+    info!("Cleaned up {} RIST test links", count);
     Ok(())
 }
 
@@ -332,6 +473,41 @@ pub async fn exec_in_rx_namespace(
     }
 }
 
+/// Bind a UDP socket inside network namespace `ns_name`.
+///
+/// Sockets keep the namespace they were created in, so the bind happens on a
+/// scratch thread that enters the namespace and the socket is handed back to
+/// the caller, whose own thread stays in its original namespace.
+#[cfg(target_os = "linux")]
+pub fn udp_socket_in<A: std::net::ToSocketAddrs>(
+    ns_name: &str,
+    bind_addr: A,
+) -> std::io::Result<std::net::UdpSocket> {
+    let addrs: Vec<std::net::SocketAddr> = bind_addr.to_socket_addrs()?.collect();
+    let ns = crate::nsapi::Namespace::from_existing(ns_name);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let _guard = ns.enter()?;
+            std::net::UdpSocket::bind(&addrs[..])
+        })
+        .join()
+        .map_err(|_| std::io::Error::other(format!("namespace thread for {} panicked", ns_name)))?
+    })
+}
+
+/// Tokio variant of `udp_socket_in`; must be called from within a runtime
+#[cfg(target_os = "linux")]
+pub async fn tokio_udp_socket_in(
+    ns_name: &str,
+    bind_addr: std::net::SocketAddr,
+) -> std::io::Result<tokio::net::UdpSocket> {
+    let socket = crate::nsapi::Namespace::from_existing(ns_name)
+        .run_blocking(move || std::net::UdpSocket::bind(bind_addr))
+        .await?;
+    socket.set_nonblocking(true)?;
+    tokio::net::UdpSocket::from_std(socket)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,12 +535,15 @@ mod tests {
                 reorder_pct: 0.0,
                 duplicate_pct: 0.0,
                 loss_corr_pct: 0.0,
+                port_filter: Vec::new(),
             },
+            tx_ifindex: None,
+            rx_ifindex: None,
         };
 
         // Test creation
         match create_shaped_veth_pair(&qdisc, &config).await {
-            Ok(()) => {
+            Ok(guard) => {
                 println!("Successfully created shaped veth pair with namespace isolation");
 
                 // Verify TX interface exists in root namespace
@@ -386,9 +565,7 @@ mod tests {
                 assert!(rx_check.is_ok() && rx_check.unwrap().status.success());
 
                 // Test cleanup
-                cleanup_shaped_veth_pair(&qdisc, &config)
-                    .await
-                    .expect("cleanup");
+                guard.cleanup(&qdisc).await.expect("cleanup");
                 println!("Successfully cleaned up shaped veth pair");
             }
             Err(e) => {
@@ -67,7 +67,32 @@ impl Namespace {
         Ok(out)
     }
 
+    /// Run a blocking closure inside the namespace on a dedicated thread.
+    ///
+    /// `setns(2)` only affects the calling thread, so entering a namespace on a
+    /// Tokio worker would leak it into unrelated tasks. This spawns a scratch
+    /// thread, enters the namespace there, and awaits the result without
+    /// blocking the executor.
+    pub async fn run_blocking<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let ns = self.clone();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        std::thread::Builder::new()
+            .name(format!("netns-{}", self.name))
+            .spawn(move || {
+                let result = ns.enter().and_then(|_guard| f());
+                let _ = tx.send(result);
+            })?;
+        rx.await
+            .map_err(|_| Error::other(format!("namespace thread for {} panicked", self.name)))?
+    }
+
     /// Enter the namespace on the current thread; guard restores on drop
+    ///
+    /// Prefer `run_blocking` from async code.
     pub fn enter(&self) -> Result<NamespaceGuard> {
         let orig = File::open("/proc/self/ns/net")?;
         let target = open_netns_file(&self.name)?;
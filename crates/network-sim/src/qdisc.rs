@@ -1,5 +1,6 @@
 //! Qdisc management for traffic control
 
+use crate::types::{HtbConfig, NetworkParams, RuntimeError};
 use log::{debug, info, warn};
 use std::fmt;
 use std::process::Output;
@@ -46,6 +47,89 @@ impl fmt::Display for NetemConfig {
     }
 }
 
+impl From<&NetworkParams> for NetemConfig {
+    fn from(params: &NetworkParams) -> Self {
+        Self {
+            delay_us: params.delay_ms * 1000,
+            jitter_us: params.jitter_ms * 1000,
+            loss_percent: params.loss_pct * 100.0,
+            loss_correlation: params.loss_corr_pct * 100.0,
+            reorder_percent: params.reorder_pct * 100.0,
+            duplicate_percent: params.duplicate_pct * 100.0,
+            rate_bps: params.rate_kbps as u64 * 1000,
+        }
+    }
+}
+
+/// Build the `netem ...` option list for a configuration
+fn netem_options(config: &NetemConfig) -> Vec<String> {
+    let mut args: Vec<String> = vec!["netem".into()];
+
+    // Rate limiting (if requested)
+    if config.rate_bps > 0 {
+        let rate_kbit = (config.rate_bps / 1000).max(1); // kbit/s
+        args.push("rate".into());
+        args.push(format!("{}kbit", rate_kbit));
+    }
+
+    // Delay and optional jitter
+    if config.delay_us > 0 {
+        args.push("delay".into());
+        args.push(format!("{}us", config.delay_us));
+        if config.jitter_us > 0 {
+            args.push(format!("{}us", config.jitter_us));
+        }
+    }
+
+    // Packet loss
+    if config.loss_percent > 0.0 {
+        args.push("loss".into());
+        args.push(format!("{}%", config.loss_percent));
+        // netem supports optional correlation; keep it simple for now
+        if config.loss_correlation > 0.0 {
+            args.push(format!("{}%", config.loss_correlation));
+        }
+    }
+
+    // Reorder
+    if config.reorder_percent > 0.0 {
+        args.push("reorder".into());
+        args.push(format!("{}%", config.reorder_percent));
+    }
+
+    // Duplicate
+    if config.duplicate_percent > 0.0 {
+        args.push("duplicate".into());
+        args.push(format!("{}%", config.duplicate_percent));
+    }
+
+    args
+}
+
+/// Like `netem_options`, for `tc qdisc change`/`replace` of an existing netem.
+///
+/// The kernel keeps the previous rate when the rate attribute is absent, so
+/// an unlimited rate is sent explicitly as `rate 0bit`.
+fn netem_change_options(config: &NetemConfig) -> Vec<String> {
+    let mut args = netem_options(config);
+    if config.rate_bps == 0 {
+        args.push("rate".into());
+        args.push("0bit".into());
+    }
+    args
+}
+
+/// Map stderr of a failed `tc` invocation onto a typed error
+pub(crate) fn tc_failure(interface: &str, stderr: &str) -> QdiscError {
+    if stderr.contains("Operation not permitted") {
+        return QdiscError::PermissionDenied;
+    }
+    if stderr.contains("Cannot find device") {
+        return QdiscError::InterfaceNotFound(interface.to_string());
+    }
+    QdiscError::CommandFailed(stderr.to_string())
+}
+
 /// Manager for qdisc traffic control
 pub struct QdiscManager {}
 
@@ -66,6 +150,15 @@ impl QdiscManager {
         Ok(out)
     }
 
+    async fn run_tc_check(&self, interface: &str, args: &[&str]) -> Result<(), QdiscError> {
+        let out = self.run_tc(args).await?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            return Err(tc_failure(interface, &stderr));
+        }
+        Ok(())
+    }
+
     async fn interface_exists(&self, interface: &str) -> Result<bool, QdiscError> {
         let out = self
             .run_ip(&["-o", "link", "show", "dev", interface])
@@ -111,67 +204,441 @@ impl QdiscManager {
         // Prefer netem's built-in rate limiting for simplicity and reliability.
         // We'll attach a single netem qdisc at root and pass along rate/delay/loss/etc.
         // no HTB usage
+        let mut netem_args: Vec<String> = vec![
+            "qdisc".into(),
+            "add".into(),
+            "dev".into(),
+            interface.into(),
+            "root".into(),
+            "handle".into(),
+            "10:".into(),
+        ];
+        netem_args.extend(netem_options(&config));
+
+        debug!("Applying netem with args: {:?}", netem_args);
+        let out = self
+            .run_tc(&netem_args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+            .await?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            if stderr.contains("Operation not permitted") {
+                return Err(QdiscError::PermissionDenied);
+            }
+            if stderr.contains("Cannot find device") {
+                return Err(QdiscError::InterfaceNotFound(interface.to_string()));
+            }
+            return Err(QdiscError::CommandFailed(stderr.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Configure netem so it only affects packets to the given destination ports.
+    ///
+    /// Installs a root prio qdisc (handle 1:) with two bands: band 1:1 carries
+    /// netem (handle 10:) and receives traffic matched by u32 dport filters,
+    /// band 1:2 is the unimpaired default for everything else.
+    pub async fn configure_interface_filtered(
+        &self,
+        interface: &str,
+        config: NetemConfig,
+        dst_ports: &[u16],
+    ) -> Result<(), QdiscError> {
+        if dst_ports.is_empty() {
+            return self.configure_interface(interface, config).await;
+        }
+        info!(
+            "Configuring interface {} with {} for dst ports {:?}",
+            interface, config, dst_ports
+        );
+
+        if !self.interface_exists(interface).await? {
+            return Err(QdiscError::InterfaceNotFound(interface.to_string()));
+        }
+
+        let _ = self
+            .run_tc(&["qdisc", "del", "dev", interface, "root"])
+            .await;
+
+        self.install_port_filtered(None, interface, &config, dst_ports)
+            .await
+    }
 
-        // Build netem arguments
-        let mut netem_args: Vec<String> =
-            vec!["qdisc".into(), "add".into(), "dev".into(), interface.into()];
+    /// Like configure_interface_filtered, but within a namespace
+    #[cfg(target_os = "linux")]
+    pub async fn configure_interface_filtered_in_ns(
+        &self,
+        ns: &str,
+        interface: &str,
+        config: NetemConfig,
+        dst_ports: &[u16],
+    ) -> Result<(), QdiscError> {
+        if dst_ports.is_empty() {
+            return self.configure_interface_in_ns(ns, interface, config).await;
+        }
+        info!(
+            "[ns={}] Configuring interface {} with {} for dst ports {:?}",
+            ns, interface, config, dst_ports
+        );
 
-        // Always attach netem at root
-        netem_args.extend(["root".into(), "handle".into(), "10:".into()]);
+        let _ = self
+            .run_ip(&[
+                "netns", "exec", ns, "tc", "qdisc", "del", "dev", interface, "root",
+            ])
+            .await;
 
-        netem_args.push("netem".into());
+        self.install_port_filtered(Some(ns), interface, &config, dst_ports)
+            .await
+    }
 
-        // Rate limiting (if requested)
-        if config.rate_bps > 0 {
-            let rate_kbit = (config.rate_bps / 1000).max(1); // kbit/s
-            netem_args.push("rate".into());
-            netem_args.push(format!("{}kbit", rate_kbit));
+    /// Run a tc command in namespace `ns` (None = root), mapping failures
+    async fn run_tc_check_ns(
+        &self,
+        ns: Option<&str>,
+        interface: &str,
+        args: &[&str],
+    ) -> Result<(), QdiscError> {
+        let Some(ns) = ns else {
+            return self.run_tc_check(interface, args).await;
+        };
+        let mut full: Vec<&str> = vec!["netns", "exec", ns, "tc"];
+        full.extend_from_slice(args);
+        let out = self.run_ip(&full).await?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            return Err(tc_failure(interface, &stderr));
         }
+        Ok(())
+    }
 
-        // Delay and optional jitter
-        if config.delay_us > 0 {
-            netem_args.push("delay".into());
-            netem_args.push(format!("{}us", config.delay_us));
-            if config.jitter_us > 0 {
-                netem_args.push(format!("{}us", config.jitter_us));
+    /// Install the prio + netem + u32 filter tree on an interface without a root qdisc
+    async fn install_port_filtered(
+        &self,
+        ns: Option<&str>,
+        interface: &str,
+        config: &NetemConfig,
+        dst_ports: &[u16],
+    ) -> Result<(), QdiscError> {
+        // All priorities map to band index 1 (class 1:2): unimpaired by default
+        let mut prio_args: Vec<&str> = vec![
+            "qdisc", "add", "dev", interface, "root", "handle", "1:", "prio", "bands", "2",
+            "priomap",
+        ];
+        prio_args.extend(std::iter::repeat_n("1", 16));
+        self.run_tc_check_ns(ns, interface, &prio_args).await?;
+
+        let mut netem_args: Vec<String> = vec![
+            "qdisc".into(),
+            "add".into(),
+            "dev".into(),
+            interface.into(),
+            "parent".into(),
+            "1:1".into(),
+            "handle".into(),
+            "10:".into(),
+        ];
+        netem_args.extend(netem_options(config));
+        self.run_tc_check_ns(
+            ns,
+            interface,
+            &netem_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        )
+        .await?;
+
+        for port in dst_ports {
+            let port = port.to_string();
+            self.run_tc_check_ns(
+                ns,
+                interface,
+                &[
+                    "filter", "add", "dev", interface, "parent", "1:", "protocol", "ip", "prio",
+                    "1", "u32", "match", "ip", "dport", &port, "0xffff", "flowid", "1:1",
+                ],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Run several tc commands through one `tc -batch` process.
+    ///
+    /// Returns `Ok(None)` if every command succeeded. Without `force` tc stops
+    /// at the first failure, returned as its zero-based index (`None` when tc
+    /// did not report one) and stderr; earlier commands stay applied. With
+    /// `force` all commands are attempted.
+    pub async fn run_tc_batch(
+        &self,
+        commands: &[String],
+        force: bool,
+    ) -> Result<Option<(Option<usize>, String)>, QdiscError> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut args = vec!["-batch", "-"];
+        if force {
+            args.insert(0, "-force");
+        }
+        debug!("tc {:?} with {} commands", args, commands.len());
+        let mut child = Command::new("tc")
+            .args(&args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let mut script = commands.join("\n");
+            script.push('\n');
+            stdin.write_all(script.as_bytes()).await?;
+        }
+        let out = child.wait_with_output().await?;
+        if out.status.success() {
+            return Ok(None);
+        }
+        let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+        let index = batch_failed_line(&stderr).map(|line| line.saturating_sub(1));
+        Ok(Some((index, stderr)))
+    }
+
+    /// The `tc -batch` line that installs `config` as the root netem qdisc
+    pub fn netem_batch_command(interface: &str, config: &NetemConfig) -> String {
+        let mut parts: Vec<String> = vec![
+            "qdisc".into(),
+            "replace".into(),
+            "dev".into(),
+            interface.into(),
+            "root".into(),
+            "handle".into(),
+            "10:".into(),
+        ];
+        parts.extend(netem_change_options(config));
+        parts.join(" ")
+    }
+
+    /// The `tc -batch` line that puts back the root qdisc recorded in `state`.
+    ///
+    /// A kernel default root (handle 0:) or no root is restored by deleting
+    /// the root qdisc; a root netem is recreated from its delay, jitter,
+    /// loss (with correlation), reorder, duplicate and rate. Returns `None`
+    /// for other roots, and for a netem root with options the snapshot did
+    /// not capture, since neither can be recreated faithfully.
+    pub fn restore_batch_command(state: &QdiscState) -> Option<String> {
+        let root = match state.root() {
+            None => return Some(format!("qdisc del dev {} root", state.interface)),
+            Some(root) if root.handle == "0:" => {
+                return Some(format!("qdisc del dev {} root", state.interface))
             }
+            Some(root) if root.kind == "netem" && root.other_options.is_empty() => root,
+            Some(_) => return None,
+        };
+        let config = NetemConfig {
+            delay_us: root.delay_us.unwrap_or(0),
+            jitter_us: root.jitter_us.unwrap_or(0),
+            loss_percent: root.loss_percent.unwrap_or(0.0),
+            loss_correlation: root.loss_correlation.unwrap_or(0.0),
+            reorder_percent: root.reorder_percent.unwrap_or(0.0),
+            duplicate_percent: root.duplicate_percent.unwrap_or(0.0),
+            rate_bps: root.rate_bps.unwrap_or(0),
+        };
+        let mut parts: Vec<String> = vec![
+            "qdisc".into(),
+            "replace".into(),
+            "dev".into(),
+            state.interface.clone(),
+            "root".into(),
+            "handle".into(),
+            root.handle.clone(),
+        ];
+        parts.extend(netem_change_options(&config));
+        Some(parts.join(" "))
+    }
+
+    /// Whether the interface currently has our netem qdisc (handle 10:) at root
+    pub async fn has_root_netem(&self, interface: &str) -> Result<bool, QdiscError> {
+        let desc = self.describe_interface_qdisc(interface).await?;
+        Ok(desc
+            .lines()
+            .any(|l| l.contains("qdisc netem 10:") && l.contains("root")))
+    }
+
+    /// Update the root netem qdisc in place via `tc qdisc change`.
+    ///
+    /// Unlike `configure_interface`, this never removes the qdisc, so queued
+    /// packets survive and there is no window without impairment. Falls back
+    /// to a full (delete + add) configuration when no netem root is present.
+    /// Returns `true` if the change happened in place.
+    pub async fn update_interface(
+        &self,
+        interface: &str,
+        config: NetemConfig,
+    ) -> Result<bool, QdiscError> {
+        if !self.interface_exists(interface).await? {
+            return Err(QdiscError::InterfaceNotFound(interface.to_string()));
         }
 
-        // Packet loss
-        if config.loss_percent > 0.0 {
-            netem_args.push("loss".into());
-            netem_args.push(format!("{}%", config.loss_percent));
-            // netem supports optional correlation; keep it simple for now
-            if config.loss_correlation > 0.0 {
-                netem_args.push(format!("{}%", config.loss_correlation));
+        if self.has_root_netem(interface).await? {
+            let mut args: Vec<String> = vec![
+                "qdisc".into(),
+                "change".into(),
+                "dev".into(),
+                interface.into(),
+                "root".into(),
+                "handle".into(),
+                "10:".into(),
+            ];
+            args.extend(netem_change_options(&config));
+
+            debug!("Changing netem in place with args: {:?}", args);
+            let out = self
+                .run_tc(&args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+                .await?;
+            if out.status.success() {
+                info!("Updated interface {} in place with {}", interface, config);
+                return Ok(true);
             }
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            let err = tc_failure(interface, &stderr);
+            if !matches!(err, QdiscError::CommandFailed(_)) {
+                return Err(err);
+            }
+            warn!(
+                "In-place change on {} failed ({}), replacing qdisc",
+                interface,
+                stderr.trim()
+            );
         }
 
-        // Reorder
-        if config.reorder_percent > 0.0 {
-            netem_args.push("reorder".into());
-            netem_args.push(format!("{}%", config.reorder_percent));
+        self.configure_interface(interface, config).await?;
+        Ok(false)
+    }
+
+    /// Configure hierarchical shaping: a root HTB (handle 1:) with a parent
+    /// class 1:1, one leaf class per entry under it, optional netem leaves,
+    /// and u32 filters steering destination ports into their class. Leaves
+    /// borrow spare bandwidth through the parent up to their ceil. Replaces
+    /// any existing root qdisc.
+    pub async fn configure_htb(
+        &self,
+        interface: &str,
+        config: &HtbConfig,
+    ) -> Result<(), QdiscError> {
+        if config.classes.is_empty() {
+            return Err(QdiscError::InvalidArgs("HTB config has no classes".into()));
+        }
+        if config.default_class >= config.classes.len() {
+            return Err(QdiscError::InvalidArgs(format!(
+                "default class {} out of range ({} classes)",
+                config.default_class,
+                config.classes.len()
+            )));
         }
+        for (i, class) in config.classes.iter().enumerate() {
+            if class.rate_kbps == 0 || class.ceil_kbps < class.rate_kbps {
+                return Err(QdiscError::InvalidArgs(format!(
+                    "class {}: rate {} kbps / ceil {} kbps",
+                    i, class.rate_kbps, class.ceil_kbps
+                )));
+            }
+        }
+
+        info!(
+            "Configuring HTB on {} with {} classes",
+            interface,
+            config.classes.len()
+        );
 
-        // Duplicate
-        if config.duplicate_percent > 0.0 {
-            netem_args.push("duplicate".into());
-            netem_args.push(format!("{}%", config.duplicate_percent));
+        if !self.interface_exists(interface).await? {
+            return Err(QdiscError::InterfaceNotFound(interface.to_string()));
         }
 
-        debug!("Applying netem with args: {:?}", netem_args);
-        let out = self
-            .run_tc(&netem_args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+        let _ = self
+            .run_tc(&["qdisc", "del", "dev", interface, "root"])
+            .await;
+
+        let default_minor = format!("{:x}", HtbConfig::class_minor(config.default_class));
+        self.run_tc_check(
+            interface,
+            &[
+                "qdisc",
+                "add",
+                "dev",
+                interface,
+                "root",
+                "handle",
+                "1:",
+                "htb",
+                "default",
+                &default_minor,
+            ],
+        )
+        .await?;
+
+        let parent_id = format!("1:{:x}", HtbConfig::PARENT_MINOR);
+        let parent_rate = format!("{}kbit", config.parent_rate_kbps());
+        self.run_tc_check(
+            interface,
+            &[
+                "class",
+                "add",
+                "dev",
+                interface,
+                "parent",
+                "1:",
+                "classid",
+                &parent_id,
+                "htb",
+                "rate",
+                &parent_rate,
+                "ceil",
+                &parent_rate,
+            ],
+        )
+        .await?;
+
+        for (i, class) in config.classes.iter().enumerate() {
+            let classid = format!("1:{:x}", HtbConfig::class_minor(i));
+            let rate = format!("{}kbit", class.rate_kbps);
+            let ceil = format!("{}kbit", class.ceil_kbps);
+            self.run_tc_check(
+                interface,
+                &[
+                    "class", "add", "dev", interface, "parent", &parent_id, "classid", &classid,
+                    "htb", "rate", &rate, "ceil", &ceil,
+                ],
+            )
             .await?;
-        if !out.status.success() {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            if stderr.contains("Operation not permitted") {
-                return Err(QdiscError::PermissionDenied);
+
+            if let Some(params) = &class.params {
+                let leaf_handle = format!("{:x}:", 0x100 + i);
+                let mut args: Vec<String> = vec![
+                    "qdisc".into(),
+                    "add".into(),
+                    "dev".into(),
+                    interface.into(),
+                    "parent".into(),
+                    classid.clone(),
+                    "handle".into(),
+                    leaf_handle,
+                ];
+                args.extend(netem_options(&NetemConfig::from(params)));
+                self.run_tc_check(
+                    interface,
+                    &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                )
+                .await?;
             }
-            if stderr.contains("Cannot find device") {
-                return Err(QdiscError::InterfaceNotFound(interface.to_string()));
+
+            for port in &class.dst_ports {
+                let port = port.to_string();
+                self.run_tc_check(
+                    interface,
+                    &[
+                        "filter", "add", "dev", interface, "parent", "1:", "protocol", "ip",
+                        "prio", "1", "u32", "match", "ip", "dport", &port, "0xffff", "flowid",
+                        &classid,
+                    ],
+                )
+                .await?;
             }
-            return Err(QdiscError::CommandFailed(stderr.to_string()));
         }
 
         Ok(())
@@ -211,50 +678,15 @@ impl QdiscManager {
             "root".into(),
             "handle".into(),
             "10:".into(),
-            "netem".into(),
         ];
-
-        if config.rate_bps > 0 {
-            let rate_kbit = (config.rate_bps / 1000).max(1);
-            args.push("rate".into());
-            args.push(format!("{}kbit", rate_kbit));
-        }
-        if config.delay_us > 0 {
-            args.push("delay".into());
-            args.push(format!("{}us", config.delay_us));
-            if config.jitter_us > 0 {
-                args.push(format!("{}us", config.jitter_us));
-            }
-        }
-        if config.loss_percent > 0.0 {
-            args.push("loss".into());
-            args.push(format!("{}%", config.loss_percent));
-            if config.loss_correlation > 0.0 {
-                args.push(format!("{}%", config.loss_correlation));
-            }
-        }
-        if config.reorder_percent > 0.0 {
-            args.push("reorder".into());
-            args.push(format!("{}%", config.reorder_percent));
-        }
-        if config.duplicate_percent > 0.0 {
-            args.push("duplicate".into());
-            args.push(format!("{}%", config.duplicate_percent));
-        }
+        args.extend(netem_options(&config));
 
         let out = tokio::process::Command::new("ip")
             .args(args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
             .output()
             .await?;
         if !out.status.success() {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            if stderr.contains("Operation not permitted") {
-                return Err(QdiscError::PermissionDenied);
-            }
-            if stderr.contains("Cannot find device") {
-                return Err(QdiscError::InterfaceNotFound(interface.to_string()));
-            }
-            return Err(QdiscError::CommandFailed(stderr.to_string()));
+            return Err(tc_failure(interface, &String::from_utf8_lossy(&out.stderr)));
         }
         Ok(())
     }
@@ -336,7 +768,28 @@ impl QdiscManager {
         config: NetemConfig,
     ) -> Result<(), QdiscError> {
         info!("Configuring ingress for {} with {}", interface, config);
+        let ifb = self.redirect_ingress(interface).await?;
+        self.configure_interface(&ifb, config).await
+    }
 
+    /// Like configure_ingress, but only impairs packets to the given destination ports
+    pub async fn configure_ingress_filtered(
+        &self,
+        interface: &str,
+        config: NetemConfig,
+        dst_ports: &[u16],
+    ) -> Result<(), QdiscError> {
+        info!(
+            "Configuring ingress for {} with {} for dst ports {:?}",
+            interface, config, dst_ports
+        );
+        let ifb = self.redirect_ingress(interface).await?;
+        self.configure_interface_filtered(&ifb, config, dst_ports)
+            .await
+    }
+
+    /// Redirect all ingress traffic of `interface` to its IFB; returns the IFB name
+    async fn redirect_ingress(&self, interface: &str) -> Result<String, QdiscError> {
         if !self.interface_exists(interface).await? {
             return Err(QdiscError::InterfaceNotFound(interface.to_string()));
         }
@@ -381,8 +834,7 @@ impl QdiscManager {
             }
         }
 
-        // Apply shaping on IFB as egress
-        self.configure_interface(&ifb, config).await
+        Ok(ifb)
     }
 
     /// Clear ingress shaping: remove ingress qdisc/filter and delete IFB device
@@ -571,6 +1023,61 @@ impl QdiscManager {
         })
     }
 
+    /// Read back installed qdiscs, their key parameters and counters
+    pub async fn get_qdisc_state(&self, interface: &str) -> Result<QdiscState, QdiscError> {
+        let out = self
+            .run_tc(&["-s", "qdisc", "show", "dev", interface])
+            .await?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            return Err(tc_failure(interface, &stderr));
+        }
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let mut state = QdiscState::parse(interface, &stdout);
+
+        let out = self
+            .run_tc(&["-s", "class", "show", "dev", interface])
+            .await?;
+        if out.status.success() {
+            state.classes = QdiscState::parse_classes(&String::from_utf8_lossy(&out.stdout));
+        }
+        Ok(state)
+    }
+
+    /// Like get_qdisc_state, but within a namespace
+    #[cfg(target_os = "linux")]
+    pub async fn get_qdisc_state_in_ns(
+        &self,
+        ns: &str,
+        interface: &str,
+    ) -> Result<QdiscState, QdiscError> {
+        let out = tokio::process::Command::new("ip")
+            .args([
+                "netns", "exec", ns, "tc", "-s", "qdisc", "show", "dev", interface,
+            ])
+            .output()
+            .await
+            .map_err(QdiscError::from)?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            return Err(tc_failure(interface, &stderr));
+        }
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let mut state = QdiscState::parse(interface, &stdout);
+
+        let out = tokio::process::Command::new("ip")
+            .args([
+                "netns", "exec", ns, "tc", "-s", "class", "show", "dev", interface,
+            ])
+            .output()
+            .await
+            .map_err(QdiscError::from)?;
+        if out.status.success() {
+            state.classes = QdiscState::parse_classes(&String::from_utf8_lossy(&out.stdout));
+        }
+        Ok(state)
+    }
+
     /// Heuristic check whether tc can be used (indicates NET_ADMIN or equivalent capability)
     pub async fn has_net_admin(&self) -> bool {
         match self.run_tc(&["qdisc", "show"]).await {
@@ -578,6 +1085,119 @@ impl QdiscManager {
             Err(_) => false,
         }
     }
+
+    /// Detect which qdisc kinds the running kernel supports.
+    ///
+    /// With NET_ADMIN each kind is added to a scratch dummy interface;
+    /// otherwise loaded modules are inspected, which misses built-in
+    /// schedulers (see `QdiscCapabilities::probed`).
+    pub async fn capabilities(&self) -> QdiscCapabilities {
+        const SCRATCH: &str = "nsim_cap0";
+        let created = self
+            .run_ip(&["link", "add", SCRATCH, "type", "dummy"])
+            .await
+            .map(|out| out.status.success())
+            .unwrap_or(false);
+
+        let mut caps = QdiscCapabilities {
+            probed: created,
+            ..Default::default()
+        };
+        for kind in QdiscCapabilities::KINDS {
+            let available = if created {
+                let mut args = vec!["qdisc", "add", "dev", SCRATCH, "root", kind];
+                if kind == "tbf" {
+                    args.extend(["rate", "1mbit", "burst", "32kbit", "latency", "400ms"]);
+                }
+                let ok = self
+                    .run_tc(&args)
+                    .await
+                    .map(|out| out.status.success())
+                    .unwrap_or(false);
+                let _ = self.run_tc(&["qdisc", "del", "dev", SCRATCH, "root"]).await;
+                ok
+            } else {
+                module_loaded(&format!("sch_{}", kind))
+            };
+            caps.set(kind, available);
+        }
+
+        if created {
+            let _ = self.run_ip(&["link", "del", SCRATCH]).await;
+        }
+        debug!("Qdisc capabilities: {:?}", caps);
+        caps
+    }
+}
+
+fn module_loaded(module: &str) -> bool {
+    let listed = std::fs::read_to_string("/proc/modules")
+        .map(|modules| {
+            modules
+                .lines()
+                .any(|line| line.split_whitespace().next() == Some(module))
+        })
+        .unwrap_or(false);
+    listed || std::path::Path::new("/sys/module").join(module).exists()
+}
+
+/// Qdisc kinds available in the running kernel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QdiscCapabilities {
+    pub netem: bool,
+    pub tbf: bool,
+    pub fq_codel: bool,
+    pub htb: bool,
+    /// True if kinds were verified on a scratch interface rather than
+    /// guessed from loaded modules
+    pub probed: bool,
+}
+
+impl QdiscCapabilities {
+    pub const KINDS: [&'static str; 4] = ["netem", "tbf", "fq_codel", "htb"];
+
+    pub fn has(&self, kind: &str) -> bool {
+        match kind {
+            "netem" => self.netem,
+            "tbf" => self.tbf,
+            "fq_codel" => self.fq_codel,
+            "htb" => self.htb,
+            _ => false,
+        }
+    }
+
+    /// Fail with `RuntimeError::MissingQdisc` if `kind` is unavailable
+    pub fn require(&self, kind: &str) -> Result<(), RuntimeError> {
+        if self.has(kind) {
+            Ok(())
+        } else {
+            Err(RuntimeError::MissingQdisc(kind.to_string()))
+        }
+    }
+
+    fn set(&mut self, kind: &str, available: bool) {
+        match kind {
+            "netem" => self.netem = available,
+            "tbf" => self.tbf = available,
+            "fq_codel" => self.fq_codel = available,
+            "htb" => self.htb = available,
+            _ => {}
+        }
+    }
+}
+
+/// One-based line number from tc's "Command failed <file>:<line>" message
+fn batch_failed_line(stderr: &str) -> Option<usize> {
+    stderr
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Command failed "))
+        .and_then(|rest| rest.rsplit(':').next())
+        .and_then(|n| n.trim().parse().ok())
+}
+
+/// Whether tc stderr reports a scheduler the kernel doesn't know
+pub(crate) fn is_unknown_qdisc(stderr: &str) -> bool {
+    stderr.contains("qdisc kind is unknown") || stderr.contains("Unknown qdisc")
 }
 
 impl Default for QdiscManager {
@@ -593,3 +1213,535 @@ pub struct InterfaceStats {
     pub sent_packets: u64,
     pub dropped: u64,
 }
+
+/// Counters reported by the kernel for a single qdisc
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QdiscStats {
+    pub sent_bytes: u64,
+    pub sent_packets: u64,
+    pub dropped: u64,
+    pub overlimits: u64,
+    pub requeues: u64,
+    pub backlog_bytes: u64,
+    pub backlog_packets: u64,
+}
+
+/// One installed qdisc as reported by `tc -s qdisc show`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QdiscEntry {
+    /// Qdisc kind, e.g. "netem", "tbf", "htb"
+    pub kind: String,
+    /// Handle, e.g. "10:"
+    pub handle: String,
+    /// Parent handle, `None` for the root qdisc
+    pub parent: Option<String>,
+    /// Rate limit in bits per second (netem/tbf)
+    pub rate_bps: Option<u64>,
+    /// Base delay in microseconds (netem)
+    pub delay_us: Option<u32>,
+    /// Delay jitter in microseconds (netem)
+    pub jitter_us: Option<u32>,
+    /// Loss percentage 0-100 (netem)
+    pub loss_percent: Option<f32>,
+    /// Loss correlation percentage 0-100 (netem)
+    pub loss_correlation: Option<f32>,
+    /// Reorder percentage 0-100 (netem)
+    pub reorder_percent: Option<f32>,
+    /// Duplicate percentage 0-100 (netem)
+    pub duplicate_percent: Option<f32>,
+    /// Netem options not captured by the fields above, as printed by tc
+    pub other_options: Vec<String>,
+    pub stats: QdiscStats,
+}
+
+/// One traffic class (e.g. an HTB class) as reported by `tc -s class show`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassEntry {
+    /// Class kind, e.g. "htb"
+    pub kind: String,
+    /// Class id, e.g. "1:2"
+    pub classid: String,
+    /// Parent class id, `None` when attached directly to the root qdisc
+    pub parent: Option<String>,
+    /// Handle of the leaf qdisc, if any
+    pub leaf: Option<String>,
+    pub rate_bps: Option<u64>,
+    pub ceil_bps: Option<u64>,
+    pub stats: QdiscStats,
+}
+
+/// Snapshot of the qdisc configuration of an interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QdiscState {
+    pub interface: String,
+    pub qdiscs: Vec<QdiscEntry>,
+    /// Classes of classful qdiscs (HTB); empty for classless setups
+    pub classes: Vec<ClassEntry>,
+}
+
+impl QdiscState {
+    /// The root qdisc, if any
+    pub fn root(&self) -> Option<&QdiscEntry> {
+        self.qdiscs.iter().find(|q| q.parent.is_none())
+    }
+
+    /// First qdisc of the given kind
+    pub fn find(&self, kind: &str) -> Option<&QdiscEntry> {
+        self.qdiscs.iter().find(|q| q.kind == kind)
+    }
+
+    /// Class with the given id, e.g. "1:2"
+    pub fn class(&self, classid: &str) -> Option<&ClassEntry> {
+        self.classes.iter().find(|c| c.classid == classid)
+    }
+
+    /// Parse the output of `tc -s qdisc show dev <iface>`
+    pub fn parse(interface: &str, output: &str) -> Self {
+        let mut qdiscs: Vec<QdiscEntry> = Vec::new();
+
+        for line in output.lines() {
+            let l = line.trim();
+            let toks: Vec<&str> = l.split_whitespace().collect();
+            match toks.first() {
+                Some(&"qdisc") => qdiscs.push(parse_qdisc_line(&toks)),
+                Some(_) => {
+                    if let Some(q) = qdiscs.last_mut() {
+                        parse_stats_line(&mut q.stats, &toks);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Self {
+            interface: interface.to_string(),
+            qdiscs,
+            classes: Vec::new(),
+        }
+    }
+
+    /// Parse the output of `tc -s class show dev <iface>`
+    pub fn parse_classes(output: &str) -> Vec<ClassEntry> {
+        let mut classes: Vec<ClassEntry> = Vec::new();
+
+        for line in output.lines() {
+            let toks: Vec<&str> = line.split_whitespace().collect();
+            match toks.first() {
+                Some(&"class") => classes.push(parse_class_line(&toks)),
+                Some(_) => {
+                    if let Some(c) = classes.last_mut() {
+                        parse_stats_line(&mut c.stats, &toks);
+                    }
+                }
+                None => {}
+            }
+        }
+        classes
+    }
+}
+
+/// Fill counters from a "Sent ..." or "backlog ..." line
+fn parse_stats_line(stats: &mut QdiscStats, toks: &[&str]) {
+    match toks.first() {
+        Some(&"Sent") => {
+            // "Sent 12345 bytes 67 pkt (dropped 1, overlimits 0 requeues 0)"
+            stats.sent_bytes = toks.get(1).and_then(|v| v.parse().ok()).unwrap_or(0);
+            stats.sent_packets = toks.get(3).and_then(|v| v.parse().ok()).unwrap_or(0);
+            stats.dropped = counter_after(toks, "(dropped");
+            stats.overlimits = counter_after(toks, "overlimits");
+            stats.requeues = counter_after(toks, "requeues");
+        }
+        Some(&"backlog") => {
+            // "backlog 1514b 1p requeues 0"
+            stats.backlog_bytes = toks.get(1).and_then(|v| parse_size(v)).unwrap_or(0);
+            stats.backlog_packets = toks
+                .get(2)
+                .and_then(|v| v.trim_end_matches('p').parse().ok())
+                .unwrap_or(0);
+        }
+        _ => {}
+    }
+}
+
+fn parse_class_line(toks: &[&str]) -> ClassEntry {
+    // "class htb 1:1 root leaf 100: prio 0 rate 5Mbit ceil 5Mbit burst 1600b cburst 1600b"
+    let mut entry = ClassEntry {
+        kind: toks.get(1).unwrap_or(&"").to_string(),
+        classid: toks.get(2).unwrap_or(&"").to_string(),
+        ..Default::default()
+    };
+    let mut i = 3;
+    while i < toks.len() {
+        match toks[i] {
+            "parent" => {
+                entry.parent = toks.get(i + 1).map(|p| p.to_string());
+                i += 1;
+            }
+            "leaf" => {
+                entry.leaf = toks.get(i + 1).map(|p| p.to_string());
+                i += 1;
+            }
+            "rate" => {
+                entry.rate_bps = toks.get(i + 1).and_then(|v| parse_rate(v));
+                i += 1;
+            }
+            "ceil" => {
+                entry.ceil_bps = toks.get(i + 1).and_then(|v| parse_rate(v));
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    entry
+}
+
+fn parse_qdisc_line(toks: &[&str]) -> QdiscEntry {
+    let mut entry = QdiscEntry {
+        kind: toks.get(1).unwrap_or(&"").to_string(),
+        handle: toks.get(2).unwrap_or(&"").to_string(),
+        ..Default::default()
+    };
+    let is_netem = entry.kind == "netem";
+    let percent = |i: usize| -> Option<f32> {
+        toks.get(i)
+            .and_then(|v| v.strip_suffix('%'))
+            .and_then(|v| v.parse().ok())
+    };
+
+    let mut i = 3;
+    while i < toks.len() {
+        match toks[i] {
+            "parent" => {
+                entry.parent = toks.get(i + 1).map(|p| p.to_string());
+                i += 1;
+            }
+            "refcnt" | "seed" => i += 1,
+            key @ ("limit" | "gap") if is_netem => {
+                // netem's default limit, and the gap tc sets along with reorder
+                match toks.get(i + 1) {
+                    Some(&"1000") if key == "limit" => {}
+                    Some(&"1") if key == "gap" => {}
+                    v => entry
+                        .other_options
+                        .push(format!("{} {}", key, v.unwrap_or(&""))),
+                }
+                i += 1;
+            }
+            "rate" => {
+                entry.rate_bps = toks.get(i + 1).and_then(|v| parse_rate(v));
+                i += 1;
+            }
+            "delay" if is_netem => {
+                entry.delay_us = toks.get(i + 1).and_then(|v| parse_time_us(v));
+                i += 1;
+                if let Some(j) = toks.get(i + 1).and_then(|v| parse_time_us(v)) {
+                    entry.jitter_us = Some(j);
+                    i += 1;
+                }
+                // Delay correlation has no NetemConfig field
+                if percent(i + 1).is_some() {
+                    entry
+                        .other_options
+                        .push(format!("delay-correlation {}", toks[i + 1]));
+                    i += 1;
+                }
+            }
+            "loss" if is_netem => {
+                // netem may print "loss random 1%" on some iproute2 versions
+                let mut k = i + 1;
+                if toks.get(k) == Some(&"random") {
+                    k += 1;
+                }
+                entry.loss_percent = percent(k);
+                if let Some(c) = percent(k + 1) {
+                    entry.loss_correlation = Some(c);
+                    k += 1;
+                }
+                i = k;
+            }
+            kind @ ("reorder" | "duplicate") if is_netem => {
+                let value = percent(i + 1);
+                if kind == "reorder" {
+                    entry.reorder_percent = value;
+                } else {
+                    entry.duplicate_percent = value;
+                }
+                i += 1;
+                if percent(i + 1).is_some() {
+                    entry
+                        .other_options
+                        .push(format!("{}-correlation {}", kind, toks[i + 1]));
+                    i += 1;
+                }
+            }
+            "root" => {}
+            other if is_netem => entry.other_options.push(other.to_string()),
+            _ => {}
+        }
+        i += 1;
+    }
+    entry
+}
+
+fn counter_after(toks: &[&str], key: &str) -> u64 {
+    toks.iter()
+        .position(|t| *t == key)
+        .and_then(|i| toks.get(i + 1))
+        .and_then(|v| v.trim_end_matches([',', ')']).parse().ok())
+        .unwrap_or(0)
+}
+
+/// Split "12.5Mbit" into (12.5, "Mbit")
+fn split_number(s: &str) -> Option<(f64, &str)> {
+    let idx = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let value = s[..idx].parse::<f64>().ok()?;
+    Some((value, &s[idx..]))
+}
+
+/// Parse a tc rate such as "5Mbit", "800Kbit" or "125Kbps" into bits per second
+fn parse_rate(s: &str) -> Option<u64> {
+    let (value, unit) = split_number(s)?;
+    let mult = match unit.to_ascii_lowercase().as_str() {
+        "bit" | "" => 1.0,
+        "kbit" => 1e3,
+        "mbit" => 1e6,
+        "gbit" => 1e9,
+        "tbit" => 1e12,
+        "bps" => 8.0,
+        "kbps" => 8e3,
+        "mbps" => 8e6,
+        "gbps" => 8e9,
+        _ => return None,
+    };
+    Some((value * mult).round() as u64)
+}
+
+/// Parse a tc time such as "50ms", "500us" or "1.5s" into microseconds
+fn parse_time_us(s: &str) -> Option<u32> {
+    let (value, unit) = split_number(s)?;
+    let mult = match unit {
+        "s" | "sec" | "secs" => 1e6,
+        "ms" | "msec" | "msecs" => 1e3,
+        "us" | "usec" | "usecs" => 1.0,
+        "ns" | "nsec" | "nsecs" => 1e-3,
+        _ => return None,
+    };
+    Some((value * mult).round() as u32)
+}
+
+/// Parse a tc size such as "1514b", "15Kb" or "2Mb" into bytes
+fn parse_size(s: &str) -> Option<u64> {
+    let (value, unit) = split_number(s)?;
+    let mult = match unit {
+        "b" | "" => 1.0,
+        "Kb" | "K" => 1024.0,
+        "Mb" | "M" => 1024.0 * 1024.0,
+        "Gb" | "G" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * mult).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_failed_line() {
+        let stderr = "Cannot find device \"nsim_missing\"\nCommand failed -:3\n";
+        assert_eq!(batch_failed_line(stderr), Some(3));
+        assert_eq!(batch_failed_line("Error: something else\n"), None);
+
+        let config = NetemConfig::from(&NetworkParams {
+            delay_ms: 20,
+            rate_kbps: 1_000,
+            ..Default::default()
+        });
+        assert_eq!(
+            QdiscManager::netem_batch_command("veth0", &config),
+            "qdisc replace dev veth0 root handle 10: netem rate 1000kbit delay 20000us"
+        );
+    }
+
+    #[test]
+    fn test_restore_batch_command() {
+        let netem = QdiscState::parse("veth0", NETEM_OUTPUT);
+        assert_eq!(
+            QdiscManager::restore_batch_command(&netem).as_deref(),
+            Some("qdisc replace dev veth0 root handle 10: netem rate 5000kbit delay 50000us 10000us loss 1%")
+        );
+
+        let default = QdiscState::parse("veth0", "qdisc noqueue 0: root refcnt 2\n");
+        assert_eq!(
+            QdiscManager::restore_batch_command(&default).as_deref(),
+            Some("qdisc del dev veth0 root")
+        );
+        let empty = QdiscState::parse("veth0", "");
+        assert_eq!(
+            QdiscManager::restore_batch_command(&empty).as_deref(),
+            Some("qdisc del dev veth0 root")
+        );
+
+        let htb = QdiscState::parse("veth0", "qdisc htb 1: root refcnt 2 r2q 10 default 0x2\n");
+        assert_eq!(QdiscManager::restore_batch_command(&htb), None);
+
+        let impaired = QdiscState::parse(
+            "veth0",
+            "qdisc netem 10: root refcnt 2 limit 1000 delay 20ms loss 2% 25% duplicate 1% reorder 5% gap 1 seed 42\n",
+        );
+        let root = impaired.root().unwrap();
+        assert_eq!(root.loss_correlation, Some(25.0));
+        assert_eq!(root.duplicate_percent, Some(1.0));
+        assert_eq!(root.reorder_percent, Some(5.0));
+        assert_eq!(
+            QdiscManager::restore_batch_command(&impaired).as_deref(),
+            Some("qdisc replace dev veth0 root handle 10: netem delay 20000us loss 2% 25% reorder 5% duplicate 1% rate 0bit")
+        );
+
+        // Options the snapshot can't express make the root unrestorable
+        for line in [
+            "qdisc netem 10: root refcnt 2 limit 1000 delay 20ms corrupt 1%\n",
+            "qdisc netem 10: root refcnt 2 limit 1000 delay 20ms  5ms 30%\n",
+            "qdisc netem 10: root refcnt 2 limit 1000 duplicate 1% 50%\n",
+            "qdisc netem 10: root refcnt 2 limit 5000 delay 20ms\n",
+            "qdisc netem 10: root refcnt 2 limit 1000 delay 20ms reorder 5% gap 3\n",
+        ] {
+            let state = QdiscState::parse("veth0", line);
+            assert!(!state.root().unwrap().other_options.is_empty(), "{}", line);
+            assert_eq!(
+                QdiscManager::restore_batch_command(&state),
+                None,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_change_options_clear_rate() {
+        let limited = NetemConfig::from(&NetworkParams {
+            rate_kbps: 500,
+            ..Default::default()
+        });
+        assert_eq!(netem_change_options(&limited), netem_options(&limited));
+
+        let unlimited = NetemConfig::from(&NetworkParams {
+            delay_ms: 5,
+            ..Default::default()
+        });
+        assert!(!netem_options(&unlimited).contains(&"rate".to_string()));
+        assert_eq!(
+            netem_change_options(&unlimited),
+            vec!["netem", "delay", "5000us", "rate", "0bit"]
+        );
+    }
+
+    #[test]
+    fn test_capabilities_require() {
+        let caps = QdiscCapabilities {
+            netem: true,
+            htb: true,
+            ..Default::default()
+        };
+        assert!(caps.require("netem").is_ok());
+        assert!(matches!(
+            caps.require("fq_codel"),
+            Err(RuntimeError::MissingQdisc(kind)) if kind == "fq_codel"
+        ));
+        assert!(!caps.has("cake"));
+        assert!(is_unknown_qdisc(
+            "Error: Specified qdisc kind is unknown.\n"
+        ));
+        assert!(!is_unknown_qdisc("Cannot find device \"veth0\"\n"));
+    }
+
+    const NETEM_OUTPUT: &str = "\
+qdisc netem 10: root refcnt 2 limit 1000 delay 50ms  10ms loss 1% rate 5Mbit
+ Sent 123456 bytes 789 pkt (dropped 12, overlimits 3 requeues 1)
+ backlog 1514b 1p requeues 1
+";
+
+    #[test]
+    fn test_parse_netem_state() {
+        let state = QdiscState::parse("veth0", NETEM_OUTPUT);
+        assert_eq!(state.qdiscs.len(), 1);
+        let root = state.root().expect("root qdisc");
+        assert_eq!(root.kind, "netem");
+        assert_eq!(root.handle, "10:");
+        assert_eq!(root.delay_us, Some(50_000));
+        assert_eq!(root.jitter_us, Some(10_000));
+        assert_eq!(root.loss_percent, Some(1.0));
+        assert_eq!(root.rate_bps, Some(5_000_000));
+        assert_eq!(
+            root.stats,
+            QdiscStats {
+                sent_bytes: 123456,
+                sent_packets: 789,
+                dropped: 12,
+                overlimits: 3,
+                requeues: 1,
+                backlog_bytes: 1514,
+                backlog_packets: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_qdiscs() {
+        let output = "\
+qdisc tbf 1: root refcnt 2 rate 1Mbit burst 32Kb lat 50ms
+ Sent 100 bytes 1 pkt (dropped 0, overlimits 0 requeues 0)
+ backlog 0b 0p requeues 0
+qdisc netem 10: parent 1:1 limit 1000 delay 500us
+ Sent 100 bytes 1 pkt (dropped 0, overlimits 0 requeues 0)
+ backlog 0b 0p requeues 0
+";
+        let state = QdiscState::parse("veth0", output);
+        assert_eq!(state.qdiscs.len(), 2);
+        let tbf = state.find("tbf").unwrap();
+        assert_eq!(tbf.rate_bps, Some(1_000_000));
+        assert_eq!(tbf.delay_us, None, "tbf latency is not a delay");
+        let netem = state.find("netem").unwrap();
+        assert_eq!(netem.parent.as_deref(), Some("1:1"));
+        assert_eq!(netem.delay_us, Some(500));
+        assert_eq!(netem.jitter_us, None);
+    }
+
+    #[test]
+    fn test_parse_htb_classes() {
+        let output = "\
+class htb 1:1 root leaf 100: prio 0 rate 2Mbit ceil 2Mbit burst 1600b cburst 1600b
+ Sent 250000 bytes 200 pkt (dropped 0, overlimits 40 requeues 0)
+ backlog 0b 0p requeues 0
+ lended: 200 borrowed: 0 giants: 0
+ tokens: 93750 ctokens: 93750
+
+class htb 1:2 root prio 0 rate 500Kbit ceil 1Mbit burst 1600b cburst 1600b
+ Sent 100 bytes 1 pkt (dropped 0, overlimits 0 requeues 0)
+ backlog 0b 0p requeues 0
+";
+        let classes = QdiscState::parse_classes(output);
+        assert_eq!(classes.len(), 2);
+        assert_eq!(classes[0].classid, "1:1");
+        assert_eq!(classes[0].leaf.as_deref(), Some("100:"));
+        assert_eq!(classes[0].rate_bps, Some(2_000_000));
+        assert_eq!(classes[0].stats.sent_bytes, 250000);
+        assert_eq!(classes[0].stats.overlimits, 40);
+        assert_eq!(classes[1].parent, None);
+        assert_eq!(classes[1].ceil_bps, Some(1_000_000));
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_rate("800Kbit"), Some(800_000));
+        assert_eq!(parse_rate("125Kbps"), Some(1_000_000));
+        assert_eq!(parse_rate("1.5Gbit"), Some(1_500_000_000));
+        assert_eq!(parse_time_us("1.5s"), Some(1_500_000));
+        assert_eq!(parse_time_us("20.0ms"), Some(20_000));
+        assert_eq!(parse_size("15Kb"), Some(15 * 1024));
+        assert_eq!(parse_rate("fast"), None);
+    }
+}
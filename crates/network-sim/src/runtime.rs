@@ -3,42 +3,155 @@
 //! This module provides utilities for applying fixed network parameters
 //! to qdisc configurations - no dynamic scheduling.
 
-use log::info;
+use log::{debug, info, warn};
 
-use crate::qdisc::{NetemConfig, QdiscManager};
-use crate::types::{NetworkParams, RuntimeError};
+use crate::qdisc::{
+    is_unknown_qdisc, tc_failure, NetemConfig, QdiscCapabilities, QdiscError, QdiscManager,
+    QdiscState,
+};
+use crate::types::{HtbConfig, NetworkParams, RuntimeError};
 
 /// Apply network parameters to a network interface
+///
+/// If our netem qdisc is already installed it is changed in place,
+/// otherwise a fresh root qdisc is created.
 pub async fn apply_network_params(
     qdisc_manager: &QdiscManager,
     interface: &str,
     params: &NetworkParams,
 ) -> Result<(), RuntimeError> {
-    let netem_config = NetemConfig {
-        delay_us: params.delay_ms * 1000,
-        jitter_us: params.jitter_ms * 1000,
-        loss_percent: params.loss_pct * 100.0,
-        loss_correlation: params.loss_corr_pct * 100.0,
-        reorder_percent: params.reorder_pct * 100.0,
-        duplicate_percent: params.duplicate_pct * 100.0,
-        rate_bps: params.rate_kbps as u64 * 1000,
-    };
+    update_network_params(qdisc_manager, interface, params).await?;
+    Ok(())
+}
 
-    qdisc_manager
-        .configure_interface(interface, netem_config)
-        .await?;
+/// Change network parameters on an interface without tearing down the qdisc
+///
+/// Returns `true` if the existing netem qdisc was changed in place and
+/// `false` if a new qdisc had to be installed.
+pub async fn update_network_params(
+    qdisc_manager: &QdiscManager,
+    interface: &str,
+    params: &NetworkParams,
+) -> Result<bool, RuntimeError> {
+    params.validate()?;
+    let netem_config = NetemConfig::from(params);
+
+    // Filtered setups carry prio + u32 filters that `tc qdisc change` cannot
+    // update, so they are always rebuilt
+    let in_place = if params.port_filter.is_empty() {
+        qdisc_manager
+            .update_interface(interface, netem_config)
+            .await
+            .map_err(|e| missing_qdisc(e, "netem"))?
+    } else {
+        qdisc_manager
+            .configure_interface_filtered(interface, netem_config, &params.port_filter)
+            .await
+            .map_err(|e| missing_qdisc(e, "netem"))?;
+        false
+    };
 
     info!(
-        "Applied parameters to {}: {}ms delay, {}% loss, {} kbps rate",
+        "Applied parameters to {} ({}): {}ms delay, {}% loss, {} kbps rate",
         interface,
+        if in_place { "in place" } else { "new qdisc" },
         params.delay_ms,
         params.loss_pct * 100.0,
         params.rate_kbps
     );
 
+    Ok(in_place)
+}
+
+/// Apply hierarchical (HTB) shaping with per-port flow classes
+///
+/// Use `remove_network_params` to tear it down again.
+pub async fn apply_htb_params(
+    qdisc_manager: &QdiscManager,
+    interface: &str,
+    config: &HtbConfig,
+) -> Result<(), RuntimeError> {
+    for params in config.classes.iter().filter_map(|c| c.params.as_ref()) {
+        params.validate()?;
+    }
+    qdisc_manager
+        .configure_htb(interface, config)
+        .await
+        .map_err(|e| missing_qdisc(e, "htb"))?;
+    info!(
+        "Applied HTB to {}: {}",
+        interface,
+        config
+            .classes
+            .iter()
+            .map(|c| format!("{}/{} kbps {:?}", c.rate_kbps, c.ceil_kbps, c.dst_ports))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
     Ok(())
 }
 
+/// Owns qdisc configuration applied by `apply_network_params_guarded`.
+///
+/// The root qdisc is deleted on drop (synchronous, best-effort, logged).
+#[must_use = "dropping the guard immediately removes the qdisc"]
+#[derive(Debug)]
+pub struct QdiscGuard {
+    interface: String,
+    armed: bool,
+}
+
+impl QdiscGuard {
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// Disarm the guard and keep the qdisc installed
+    pub fn leak(mut self) {
+        self.armed = false;
+    }
+
+    /// Remove the qdisc asynchronously and disarm the guard
+    pub async fn remove(mut self, qdisc_manager: &QdiscManager) -> Result<(), RuntimeError> {
+        self.armed = false;
+        remove_network_params(qdisc_manager, &self.interface).await
+    }
+}
+
+impl Drop for QdiscGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        match std::process::Command::new("tc")
+            .args(["qdisc", "del", "dev", &self.interface, "root"])
+            .output()
+        {
+            Ok(out) if !out.status.success() => {
+                debug!("Guard: no qdisc to delete on {}", self.interface)
+            }
+            Ok(_) => debug!("Guard removed qdisc from {}", self.interface),
+            Err(e) => warn!(
+                "Guard failed to remove qdisc from {}: {}",
+                self.interface, e
+            ),
+        }
+    }
+}
+
+/// Apply network parameters and return a guard that removes them on drop
+pub async fn apply_network_params_guarded(
+    qdisc_manager: &QdiscManager,
+    interface: &str,
+    params: &NetworkParams,
+) -> Result<QdiscGuard, RuntimeError> {
+    apply_network_params(qdisc_manager, interface, params).await?;
+    Ok(QdiscGuard {
+        interface: interface.to_string(),
+        armed: true,
+    })
+}
+
 /// Remove any network parameters previously applied (delete root qdisc)
 pub async fn remove_network_params(
     qdisc_manager: &QdiscManager,
@@ -55,19 +168,20 @@ pub async fn apply_ingress_params(
     interface: &str,
     params: &NetworkParams,
 ) -> Result<(), RuntimeError> {
-    let netem_config = NetemConfig {
-        delay_us: params.delay_ms * 1000,
-        jitter_us: params.jitter_ms * 1000,
-        loss_percent: params.loss_pct * 100.0,
-        loss_correlation: params.loss_corr_pct * 100.0,
-        reorder_percent: params.reorder_pct * 100.0,
-        duplicate_percent: params.duplicate_pct * 100.0,
-        rate_bps: params.rate_kbps as u64 * 1000,
-    };
+    params.validate()?;
+    let netem_config = NetemConfig::from(params);
 
-    qdisc_manager
-        .configure_ingress(interface, netem_config)
-        .await?;
+    if params.port_filter.is_empty() {
+        qdisc_manager
+            .configure_ingress(interface, netem_config)
+            .await
+            .map_err(|e| missing_qdisc(e, "netem"))?;
+    } else {
+        qdisc_manager
+            .configure_ingress_filtered(interface, netem_config, &params.port_filter)
+            .await
+            .map_err(|e| missing_qdisc(e, "netem"))?;
+    }
     info!(
         "Applied ingress params to {}: {}ms delay, {}% loss, {} kbps rate",
         interface,
@@ -88,10 +202,384 @@ pub async fn remove_ingress_params(
     Ok(())
 }
 
+/// Read back the qdisc kinds, parameters and counters installed on an interface
+pub async fn get_qdisc_state(
+    qdisc_manager: &QdiscManager,
+    interface: &str,
+) -> Result<QdiscState, RuntimeError> {
+    Ok(qdisc_manager.get_qdisc_state(interface).await?)
+}
+
+/// Outcome of `apply_network_params_batch`
+#[derive(Debug)]
+pub struct BatchReport {
+    /// One result per input entry, in input order
+    pub results: Vec<(String, Result<(), RuntimeError>)>,
+    /// Number of `tc -batch` processes spawned (1, or 2 when a rollback ran)
+    pub tc_invocations: usize,
+    /// Whether already-applied entries were restored after a failure
+    pub rolled_back: bool,
+}
+
+impl BatchReport {
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|(_, r)| r.is_ok())
+    }
+}
+
+/// Apply parameters to many interfaces through a single `tc -batch` process.
+///
+/// All entries are validated up front; nothing is applied if any is invalid.
+/// tc stops at the first failing interface. With `rollback_on_error` each
+/// interface's qdiscs are snapshotted first, and entries already applied by
+/// this batch are restored from the snapshot on failure (see
+/// `QdiscManager::restore_batch_command`). If tc does not say which entry
+/// failed, every entry is restored and reported as failed. Port filters need
+/// several dependent commands per interface and are rejected here; use
+/// `apply_network_params` for those.
+pub async fn apply_network_params_batch(
+    qdisc_manager: &QdiscManager,
+    entries: &[(&str, NetworkParams)],
+    rollback_on_error: bool,
+) -> BatchReport {
+    let mut report = BatchReport {
+        results: Vec::with_capacity(entries.len()),
+        tc_invocations: 0,
+        rolled_back: false,
+    };
+
+    let invalid: Vec<Option<RuntimeError>> = entries
+        .iter()
+        .map(|(_, params)| {
+            if !params.port_filter.is_empty() {
+                return Some(RuntimeError::InvalidParams(
+                    "port filters are not supported in batch mode".to_string(),
+                ));
+            }
+            params.validate().err()
+        })
+        .collect();
+    if invalid.iter().any(Option::is_some) {
+        for ((interface, _), err) in entries.iter().zip(invalid) {
+            let err = err.unwrap_or_else(|| {
+                RuntimeError::InvalidParams("batch aborted: another entry is invalid".to_string())
+            });
+            report.results.push((interface.to_string(), Err(err)));
+        }
+        return report;
+    }
+
+    let commands: Vec<String> = entries
+        .iter()
+        .map(|(interface, params)| {
+            QdiscManager::netem_batch_command(interface, &NetemConfig::from(params))
+        })
+        .collect();
+
+    // What to put back on each interface if the batch fails part way
+    let mut snapshots: Vec<Option<QdiscState>> = Vec::with_capacity(entries.len());
+    for (interface, _) in entries {
+        let snapshot = if rollback_on_error {
+            qdisc_manager.get_qdisc_state(interface).await.ok()
+        } else {
+            None
+        };
+        snapshots.push(snapshot);
+    }
+
+    report.tc_invocations += 1;
+    let failure = match qdisc_manager.run_tc_batch(&commands, false).await {
+        Ok(failure) => failure,
+        Err(e) => {
+            for (interface, _) in entries {
+                report.results.push((
+                    interface.to_string(),
+                    Err(RuntimeError::CommandFailed(format!(
+                        "tc -batch failed: {}",
+                        e
+                    ))),
+                ));
+            }
+            return report;
+        }
+    };
+
+    let Some((failed, stderr)) = failure else {
+        for (interface, _) in entries {
+            report.results.push((interface.to_string(), Ok(())));
+        }
+        info!(
+            "Applied parameters to {} interfaces in one batch",
+            entries.len()
+        );
+        return report;
+    };
+
+    // Entries tc may have applied before stopping; all of them when tc did
+    // not report the failing line
+    let (applied, failed_interface) = match failed {
+        Some(failed) => {
+            let failed = failed.min(entries.len().saturating_sub(1));
+            (0..failed, Some(failed))
+        }
+        None => (0..entries.len(), None),
+    };
+    warn!(
+        "Batch failed at {}: {}",
+        match failed_interface {
+            Some(i) => format!("{} ({} of {})", entries[i].0, i + 1, entries.len()),
+            None => "an unknown entry".to_string(),
+        },
+        stderr.trim()
+    );
+
+    // Put back the snapshot of every entry tc may have applied
+    let mut unrestorable = vec![false; entries.len()];
+    let mut restored = false;
+    if rollback_on_error && !applied.is_empty() {
+        let mut undo = Vec::new();
+        for i in applied.clone() {
+            match snapshots[i]
+                .as_ref()
+                .and_then(QdiscManager::restore_batch_command)
+            {
+                Some(cmd) => undo.push(cmd),
+                None => unrestorable[i] = true,
+            }
+        }
+        if !undo.is_empty() {
+            report.tc_invocations += 1;
+            match qdisc_manager.run_tc_batch(&undo, true).await {
+                Ok(None) => restored = true,
+                Ok(Some((_, undo_stderr))) => {
+                    warn!(
+                        "Rollback after batch failure failed: {}",
+                        undo_stderr.trim()
+                    )
+                }
+                Err(e) => warn!("Rollback after batch failure did not run: {}", e),
+            }
+        }
+        report.rolled_back = restored && !unrestorable.contains(&true);
+    }
+
+    let cause = failed_interface.map_or("", |i| entries[i].0);
+    for (i, (interface, _)) in entries.iter().enumerate() {
+        let result = if failed_interface.is_none() {
+            Err(RuntimeError::CommandFailed(format!(
+                "tc -batch failed at an unknown entry{}: {}",
+                if restored { " (rolled back)" } else { "" },
+                stderr.trim()
+            )))
+        } else if Some(i) == failed_interface {
+            Err(missing_qdisc(tc_failure(interface, &stderr), "netem"))
+        } else if !applied.contains(&i) {
+            Err(RuntimeError::CommandFailed(format!(
+                "not attempted: {} failed first",
+                cause
+            )))
+        } else if unrestorable[i] {
+            Err(RuntimeError::CommandFailed(format!(
+                "{} failed and the previous qdisc could not be restored",
+                cause
+            )))
+        } else if restored {
+            Err(RuntimeError::CommandFailed(format!(
+                "rolled back after {} failed",
+                cause
+            )))
+        } else {
+            Ok(())
+        };
+        report.results.push((interface.to_string(), result));
+    }
+    report
+}
+
+/// Detect which qdisc kinds the running kernel supports
+pub async fn capabilities(qdisc_manager: &QdiscManager) -> QdiscCapabilities {
+    qdisc_manager.capabilities().await
+}
+
+/// Surface "unknown qdisc kind" failures as `RuntimeError::MissingQdisc`
+fn missing_qdisc(err: QdiscError, kind: &str) -> RuntimeError {
+    match err {
+        QdiscError::CommandFailed(ref stderr) if is_unknown_qdisc(stderr) => {
+            RuntimeError::MissingQdisc(kind.to_string())
+        }
+        other => other.into(),
+    }
+}
+
+/// Resolve the current name of the interface with index `ifindex`
+///
+/// `ns` selects the namespace to look in (None = root). Indices survive
+/// renames, and names can repeat across namespaces, so callers that hold an
+/// index should resolve it right before use.
+pub async fn interface_name(ifindex: u32, ns: Option<&str>) -> Result<String, RuntimeError> {
+    list_links(ns)
+        .await?
+        .into_iter()
+        .find(|(index, _)| *index == ifindex)
+        .map(|(_, name)| name)
+        .ok_or_else(|| {
+            RuntimeError::InvalidParams(format!(
+                "no interface with index {} in {}",
+                ifindex,
+                ns.unwrap_or("root namespace")
+            ))
+        })
+}
+
+/// Look up the index of `interface` within namespace `ns` (None = root)
+pub async fn interface_index(interface: &str, ns: Option<&str>) -> Result<u32, RuntimeError> {
+    list_links(ns)
+        .await?
+        .into_iter()
+        .find(|(_, name)| name == interface)
+        .map(|(index, _)| index)
+        .ok_or_else(|| {
+            RuntimeError::InvalidParams(format!(
+                "no interface named {} in {}",
+                interface,
+                ns.unwrap_or("root namespace")
+            ))
+        })
+}
+
+/// Apply network parameters to the interface with index `ifindex`
+///
+/// `ns` selects the namespace the index belongs to (None = root); the
+/// interface is resolved and shaped inside that namespace.
+pub async fn apply_network_params_ifindex(
+    qdisc_manager: &QdiscManager,
+    ifindex: u32,
+    ns: Option<&str>,
+    params: &NetworkParams,
+) -> Result<(), RuntimeError> {
+    let interface = interface_name(ifindex, ns).await?;
+    debug!(
+        "Resolved ifindex {} in {} to {}",
+        ifindex,
+        ns.unwrap_or("root namespace"),
+        interface
+    );
+    match ns {
+        None => apply_network_params(qdisc_manager, &interface, params).await,
+        #[cfg(target_os = "linux")]
+        Some(ns) => apply_network_params_in_ns(qdisc_manager, ns, &interface, params).await,
+        #[cfg(not(target_os = "linux"))]
+        Some(ns) => Err(RuntimeError::InvalidParams(format!(
+            "namespace {} requires Linux",
+            ns
+        ))),
+    }
+}
+
+/// Apply network parameters to `interface` inside namespace `ns`
+///
+/// The root qdisc is always rebuilt; there is no in-place change here.
+#[cfg(target_os = "linux")]
+pub async fn apply_network_params_in_ns(
+    qdisc_manager: &QdiscManager,
+    ns: &str,
+    interface: &str,
+    params: &NetworkParams,
+) -> Result<(), RuntimeError> {
+    params.validate()?;
+    qdisc_manager
+        .configure_interface_filtered_in_ns(
+            ns,
+            interface,
+            NetemConfig::from(params),
+            &params.port_filter,
+        )
+        .await
+        .map_err(|e| missing_qdisc(e, "netem"))?;
+    info!("[ns={}] Applied parameters to {}", ns, interface);
+    Ok(())
+}
+
+/// Remove network parameters from the interface with index `ifindex` in `ns`
+pub async fn remove_network_params_ifindex(
+    qdisc_manager: &QdiscManager,
+    ifindex: u32,
+    ns: Option<&str>,
+) -> Result<(), RuntimeError> {
+    let interface = interface_name(ifindex, ns).await?;
+    match ns {
+        None => remove_network_params(qdisc_manager, &interface).await,
+        #[cfg(target_os = "linux")]
+        Some(ns) => {
+            qdisc_manager.clear_interface_in_ns(ns, &interface).await?;
+            info!("[ns={}] Removed network simulation from {}", ns, interface);
+            Ok(())
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(ns) => Err(RuntimeError::InvalidParams(format!(
+            "namespace {} requires Linux",
+            ns
+        ))),
+    }
+}
+
+async fn list_links(ns: Option<&str>) -> Result<Vec<(u32, String)>, RuntimeError> {
+    let mut args = Vec::new();
+    if let Some(ns) = ns {
+        args.extend(["-n", ns]);
+    }
+    args.extend(["-o", "link", "show"]);
+    let out = tokio::process::Command::new("ip")
+        .args(&args)
+        .output()
+        .await?;
+    if !out.status.success() {
+        return Err(RuntimeError::CommandFailed(format!(
+            "ip {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(parse_links(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// Parse `ip -o link show` output into (index, name) pairs
+pub(crate) fn parse_links(output: &str) -> Vec<(u32, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ": ");
+            let index = parts.next()?.trim().parse().ok()?;
+            // veth names carry their peer as "name@ifN" or "name@peer"
+            let name = parts.next()?.split('@').next()?.trim();
+            (!name.is_empty()).then(|| (index, name.to_string()))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_links() {
+        let output = "\
+1: lo: <LOOPBACK,UP,LOWER_UP> mtu 65536 qdisc noqueue state UNKNOWN mode DEFAULT group default qlen 1000\\    link/loopback 00:00:00:00:00:00 brd 00:00:00:00:00:00
+7: veth_tx@veth_rx: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc netem state UP mode DEFAULT group default qlen 1000\\    link/ether 2e:1b:7c:00:00:01 brd ff:ff:ff:ff:ff:ff
+12: eth0@if13: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc noqueue state UP mode DEFAULT group default\\    link/ether 02:42:ac:11:00:02 brd ff:ff:ff:ff:ff:ff link-netnsid 0
+";
+        let links = parse_links(output);
+        assert_eq!(
+            links,
+            vec![
+                (1, "lo".to_string()),
+                (7, "veth_tx".to_string()),
+                (12, "eth0".to_string()),
+            ]
+        );
+        assert!(parse_links("garbage\n").is_empty());
+    }
+
     #[tokio::test]
     async fn test_apply_network_params() {
         let qdisc_manager = QdiscManager::default();
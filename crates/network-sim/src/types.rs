@@ -15,10 +15,13 @@ pub enum RuntimeError {
 
     #[error("Invalid parameters: {0}")]
     InvalidParams(String),
+
+    #[error("Qdisc kind not available in this kernel: {0}")]
+    MissingQdisc(String),
 }
 
 /// Network parameters for simulation
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct NetworkParams {
     /// Delay in milliseconds
     pub delay_ms: u32,
@@ -34,9 +37,63 @@ pub struct NetworkParams {
     pub duplicate_pct: f32,
     /// Optional loss correlation percentage (0.0 to 1.0)
     pub loss_corr_pct: f32,
+    /// Destination ports the impairment applies to (empty = all traffic)
+    pub port_filter: Vec<u16>,
 }
 
+/// Largest delay or jitter accepted by `NetworkParams::validate`
+pub const MAX_DELAY_MS: u32 = 60_000;
+
+/// Largest rate accepted by `NetworkParams::validate` (100 Gbit/s)
+pub const MAX_RATE_KBPS: u32 = 100_000_000;
+
 impl NetworkParams {
+    /// Check the parameters are within the ranges netem accepts.
+    ///
+    /// Probabilities must lie in 0.0..=1.0, delay and jitter are capped at
+    /// `MAX_DELAY_MS`, and `rate_kbps` at `MAX_RATE_KBPS` (0 = unlimited).
+    pub fn validate(&self) -> Result<(), RuntimeError> {
+        let probabilities = [
+            ("loss_pct", self.loss_pct),
+            ("reorder_pct", self.reorder_pct),
+            ("duplicate_pct", self.duplicate_pct),
+            ("loss_corr_pct", self.loss_corr_pct),
+        ];
+        for (name, value) in probabilities {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(RuntimeError::InvalidParams(format!(
+                    "{} must be within 0.0..=1.0, got {}",
+                    name, value
+                )));
+            }
+        }
+        if self.delay_ms > MAX_DELAY_MS || self.jitter_ms > MAX_DELAY_MS {
+            return Err(RuntimeError::InvalidParams(format!(
+                "delay {}ms / jitter {}ms exceeds {}ms",
+                self.delay_ms, self.jitter_ms, MAX_DELAY_MS
+            )));
+        }
+        if self.rate_kbps > MAX_RATE_KBPS {
+            return Err(RuntimeError::InvalidParams(format!(
+                "rate {} kbps exceeds {} kbps",
+                self.rate_kbps, MAX_RATE_KBPS
+            )));
+        }
+        if self.port_filter.contains(&0) {
+            return Err(RuntimeError::InvalidParams(
+                "port_filter must not contain port 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Restrict the impairment to packets for these destination ports;
+    /// everything else bypasses netem
+    pub fn port_filter(mut self, ports: &[u16]) -> Self {
+        self.port_filter = ports.to_vec();
+        self
+    }
+
     /// Good network conditions
     pub fn good() -> Self {
         Self {
@@ -47,6 +104,7 @@ impl NetworkParams {
             reorder_pct: 0.0,
             duplicate_pct: 0.0,
             loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
         }
     }
 
@@ -60,6 +118,7 @@ impl NetworkParams {
             reorder_pct: 0.0,
             duplicate_pct: 0.0,
             loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
         }
     }
 
@@ -73,6 +132,232 @@ impl NetworkParams {
             reorder_pct: 0.0,
             duplicate_pct: 0.0,
             loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
+        }
+    }
+
+    /// Cellular (LTE-like) conditions: moderate rate, noticeable jitter and bursty loss
+    pub fn cellular() -> Self {
+        Self {
+            delay_ms: 50,
+            loss_pct: 0.02,   // 2%
+            rate_kbps: 4_000, // 4 Mbps
+            jitter_ms: 20,
+            reorder_pct: 0.0,
+            duplicate_pct: 0.0,
+            loss_corr_pct: 0.25,
+            port_filter: Vec::new(),
+        }
+    }
+
+    /// Geostationary satellite conditions: very high latency, low loss
+    pub fn satellite() -> Self {
+        Self {
+            delay_ms: 300,
+            loss_pct: 0.005,  // 0.5%
+            rate_kbps: 2_000, // 2 Mbps
+            jitter_ms: 10,
+            reorder_pct: 0.0,
+            duplicate_pct: 0.0,
+            loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
+        }
+    }
+}
+
+/// One HTB class: a guaranteed rate, a borrowing ceiling and the
+/// destination ports steered into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtbClass {
+    /// Guaranteed rate in kilobits per second
+    pub rate_kbps: u32,
+    /// Maximum rate when borrowing from siblings (>= rate_kbps)
+    pub ceil_kbps: u32,
+    /// UDP/TCP destination ports classified into this class
+    pub dst_ports: Vec<u16>,
+    /// Optional netem impairment attached as the class leaf qdisc
+    pub params: Option<NetworkParams>,
+}
+
+impl HtbClass {
+    /// Class with a fixed rate (ceil == rate) and no impairment
+    pub fn new(rate_kbps: u32) -> Self {
+        Self {
+            rate_kbps,
+            ceil_kbps: rate_kbps,
+            dst_ports: Vec::new(),
+            params: None,
+        }
+    }
+
+    /// Allow borrowing up to `ceil_kbps`
+    pub fn ceil(mut self, ceil_kbps: u32) -> Self {
+        self.ceil_kbps = ceil_kbps;
+        self
+    }
+
+    /// Steer traffic to these destination ports into the class
+    pub fn ports(mut self, ports: &[u16]) -> Self {
+        self.dst_ports.extend_from_slice(ports);
+        self
+    }
+
+    /// Attach a netem leaf with the given impairment
+    pub fn impair(mut self, params: NetworkParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+}
+
+/// Hierarchical shaping: root HTB with a shared parent class (1:1) and one
+/// leaf class per flow group underneath it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HtbConfig {
+    pub classes: Vec<HtbClass>,
+    /// Index into `classes` receiving unmatched traffic
+    pub default_class: usize,
+}
+
+impl HtbConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a class
+    pub fn class(mut self, class: HtbClass) -> Self {
+        self.classes.push(class);
+        self
+    }
+
+    /// Select the class receiving unmatched traffic
+    pub fn default_class(mut self, idx: usize) -> Self {
+        self.default_class = idx;
+        self
+    }
+
+    /// HTB minor id of the parent class all leaf classes borrow through
+    pub const PARENT_MINOR: u32 = 1;
+
+    /// HTB minor class id used for the class at `idx` (1:N, after the parent)
+    pub fn class_minor(idx: usize) -> u32 {
+        idx as u32 + Self::PARENT_MINOR + 1
+    }
+
+    /// Parent class rate: the sum of the leaf ceilings, so any leaf can
+    /// reach its ceiling while its siblings are idle
+    pub fn parent_rate_kbps(&self) -> u64 {
+        self.classes.iter().map(|c| c.ceil_kbps as u64).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_accepts_presets() {
+        for params in [
+            NetworkParams::good(),
+            NetworkParams::typical(),
+            NetworkParams::poor(),
+            NetworkParams::cellular(),
+            NetworkParams::satellite(),
+            NetworkParams::default(),
+        ] {
+            params.validate().expect("preset should be valid");
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range() {
+        let invalid = [
+            NetworkParams {
+                loss_pct: 1.5,
+                ..Default::default()
+            },
+            NetworkParams {
+                reorder_pct: -0.1,
+                ..Default::default()
+            },
+            NetworkParams {
+                duplicate_pct: f32::NAN,
+                ..Default::default()
+            },
+            NetworkParams {
+                delay_ms: MAX_DELAY_MS + 1,
+                ..Default::default()
+            },
+            NetworkParams {
+                jitter_ms: MAX_DELAY_MS + 1,
+                ..Default::default()
+            },
+            NetworkParams {
+                rate_kbps: MAX_RATE_KBPS + 1,
+                ..Default::default()
+            },
+            NetworkParams::default().port_filter(&[5000, 0]),
+        ];
+        for params in invalid {
+            assert!(
+                matches!(params.validate(), Err(RuntimeError::InvalidParams(_))),
+                "{:?} should be rejected",
+                params
+            );
+        }
+    }
+
+    #[test]
+    fn test_presets_are_ordered_by_quality() {
+        let presets = [
+            NetworkParams::good(),
+            NetworkParams::typical(),
+            NetworkParams::cellular(),
+            NetworkParams::poor(),
+        ];
+        for pair in presets.windows(2) {
+            assert!(pair[0].loss_pct <= pair[1].loss_pct);
+            assert!(pair[0].delay_ms <= pair[1].delay_ms);
+            assert!(pair[0].rate_kbps >= pair[1].rate_kbps);
         }
     }
+
+    #[test]
+    fn test_cellular_and_satellite_presets() {
+        let cellular = NetworkParams::cellular();
+        assert_eq!(cellular.delay_ms, 50);
+        assert_eq!(cellular.jitter_ms, 20);
+        assert_eq!(cellular.loss_pct, 0.02);
+        assert_eq!(cellular.rate_kbps, 4_000);
+        assert!(cellular.loss_corr_pct > 0.0);
+
+        let satellite = NetworkParams::satellite();
+        assert_eq!(satellite.delay_ms, 300);
+        assert_eq!(satellite.loss_pct, 0.005);
+        assert_eq!(satellite.rate_kbps, 2_000);
+        assert!(satellite.jitter_ms < satellite.delay_ms);
+    }
+
+    #[test]
+    fn test_port_filter_builder() {
+        let params = NetworkParams::poor().port_filter(&[5004]);
+        assert_eq!(params.port_filter, vec![5004]);
+        assert_eq!(params.delay_ms, NetworkParams::poor().delay_ms);
+        assert!(NetworkParams::default().port_filter.is_empty());
+    }
+
+    #[test]
+    fn test_htb_config_builder() {
+        let cfg = HtbConfig::new()
+            .class(HtbClass::new(5_000).ports(&[5004, 5005]))
+            .class(HtbClass::new(1_000).ceil(2_000))
+            .default_class(1);
+        assert_eq!(cfg.classes.len(), 2);
+        assert_eq!(cfg.classes[0].ceil_kbps, 5_000);
+        assert_eq!(cfg.classes[0].dst_ports, vec![5004, 5005]);
+        assert_eq!(cfg.classes[1].ceil_kbps, 2_000);
+        assert_eq!(cfg.default_class, 1);
+        assert_eq!(HtbConfig::class_minor(0), 2);
+        assert_eq!(HtbConfig::class_minor(1), 3);
+        assert_eq!(cfg.parent_rate_kbps(), 7_000);
+    }
 }
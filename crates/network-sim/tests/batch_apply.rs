@@ -0,0 +1,146 @@
+//! Validate batch application: one tc process for many interfaces, and
+//! rollback of already-applied entries when a later one fails.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{
+    apply_network_params_batch, get_qdisc_state, remove_network_params, NetworkParams,
+};
+
+const DUMMIES: [&str; 4] = ["nsim_bt0", "nsim_bt1", "nsim_bt2", "nsim_bt3"];
+
+fn ip(args: &[&str]) -> bool {
+    std::process::Command::new("ip")
+        .args(args)
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+fn params(delay_ms: u32) -> NetworkParams {
+    NetworkParams {
+        delay_ms,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_batch_apply_and_rollback() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping batch test: requires NET_ADMIN");
+        return;
+    }
+    for name in DUMMIES {
+        let _ = ip(&["link", "del", name]);
+        if !ip(&["link", "add", name, "type", "dummy"]) {
+            eprintln!("Skipping batch test: cannot create dummy interfaces");
+            return;
+        }
+    }
+
+    // All interfaces configured by a single tc process
+    let entries: Vec<(&str, NetworkParams)> = DUMMIES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (*name, params(10 * (i as u32 + 1))))
+        .collect();
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert!(report.is_ok(), "batch failed: {:?}", report.results);
+    assert_eq!(report.tc_invocations, 1);
+    for (name, params) in &entries {
+        let state = get_qdisc_state(&qdisc, name).await.expect("qdisc state");
+        let netem = state.find("netem").expect("netem installed");
+        assert_eq!(netem.delay_us, Some(params.delay_ms * 1000));
+    }
+
+    // Induced failure on the third entry restores the first two to the
+    // netem they had before this batch
+    let entries = [
+        (DUMMIES[0], params(50)),
+        (DUMMIES[1], params(50)),
+        ("nsim_missing", params(50)),
+        (DUMMIES[2], params(50)),
+    ];
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert!(!report.is_ok());
+    assert!(report.rolled_back);
+    assert_eq!(report.tc_invocations, 2);
+    assert_eq!(report.results.len(), entries.len());
+    assert!(report.results.iter().all(|(_, r)| r.is_err()));
+    for (name, delay_us) in [(DUMMIES[0], 10_000), (DUMMIES[1], 20_000)] {
+        let state = get_qdisc_state(&qdisc, name).await.expect("qdisc state");
+        let netem = state.find("netem").expect("previous netem restored");
+        assert_eq!(netem.delay_us, Some(delay_us), "{} not rolled back", name);
+    }
+    // Entries after the failure were never attempted
+    let state = get_qdisc_state(&qdisc, DUMMIES[2])
+        .await
+        .expect("qdisc state");
+    assert_eq!(state.find("netem").and_then(|n| n.delay_us), Some(30_000));
+
+    // Loss correlation, reorder and duplicate survive the rollback
+    let detailed = NetworkParams {
+        delay_ms: 10,
+        loss_pct: 0.02,
+        loss_corr_pct: 0.25,
+        reorder_pct: 0.05,
+        duplicate_pct: 0.01,
+        ..Default::default()
+    };
+    let report = apply_network_params_batch(&qdisc, &[(DUMMIES[0], detailed)], true).await;
+    assert!(report.is_ok(), "batch failed: {:?}", report.results);
+    let entries = [(DUMMIES[0], params(50)), ("nsim_missing", params(50))];
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert!(report.rolled_back);
+    let state = get_qdisc_state(&qdisc, DUMMIES[0])
+        .await
+        .expect("qdisc state");
+    let netem = state.find("netem").expect("previous netem restored");
+    assert_eq!(netem.delay_us, Some(10_000));
+    assert_eq!(netem.loss_percent, Some(2.0));
+    assert_eq!(netem.loss_correlation, Some(25.0));
+    assert_eq!(netem.reorder_percent, Some(5.0));
+    assert_eq!(netem.duplicate_percent, Some(1.0));
+
+    // A root with options the snapshot can't express is not claimed as restored
+    assert!(std::process::Command::new("tc")
+        .args(["qdisc", "replace", "dev", DUMMIES[0], "root", "handle", "10:"])
+        .args(["netem", "delay", "10ms", "corrupt", "1%"])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false));
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert!(!report.is_ok());
+    assert!(!report.rolled_back);
+
+    // An interface that had no qdisc before the batch gets none back
+    remove_network_params(&qdisc, DUMMIES[3])
+        .await
+        .expect("clear");
+    let entries = [(DUMMIES[3], params(50)), ("nsim_missing", params(50))];
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert!(report.rolled_back);
+    let state = get_qdisc_state(&qdisc, DUMMIES[3])
+        .await
+        .expect("qdisc state");
+    assert!(state.find("netem").is_none(), "netem left behind");
+
+    // Invalid parameters abort before any tc command runs
+    let entries = [
+        (DUMMIES[0], params(10)),
+        (
+            DUMMIES[1],
+            NetworkParams {
+                loss_pct: 3.0,
+                ..Default::default()
+            },
+        ),
+    ];
+    let report = apply_network_params_batch(&qdisc, &entries, true).await;
+    assert_eq!(report.tc_invocations, 0);
+    assert!(report.results.iter().all(|(_, r)| r.is_err()));
+
+    for name in DUMMIES {
+        let _ = ip(&["link", "del", name]);
+    }
+}
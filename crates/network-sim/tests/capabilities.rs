@@ -0,0 +1,43 @@
+//! Verify the qdisc capability probe against the running kernel.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{apply_network_params, capabilities, NetworkParams, RuntimeError};
+
+#[tokio::test]
+async fn test_capability_probe_finds_netem() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping capability probe test: requires NET_ADMIN");
+        return;
+    }
+
+    let caps = capabilities(&qdisc).await;
+    println!("Qdisc capabilities: {:?}", caps);
+    if !caps.probed {
+        eprintln!("Skipping capability probe test: cannot create dummy interfaces");
+        return;
+    }
+
+    // Every other test in this crate depends on netem
+    caps.require("netem").expect("netem available");
+    assert_eq!(caps.has("htb"), caps.htb);
+
+    // The scratch interface must not outlive the probe
+    let out = std::process::Command::new("ip")
+        .args(["link", "show", "dev", "nsim_cap0"])
+        .output()
+        .expect("ip link show");
+    assert!(!out.status.success(), "scratch interface left behind");
+}
+
+#[tokio::test]
+async fn test_invalid_params_rejected_before_tc() {
+    // Validation runs before any tc command, so this needs no privileges
+    let qdisc = QdiscManager::new();
+    let params = NetworkParams {
+        loss_pct: 2.0,
+        ..NetworkParams::typical()
+    };
+    let result = apply_network_params(&qdisc, "lo", &params).await;
+    assert!(matches!(result, Err(RuntimeError::InvalidParams(_))));
+}
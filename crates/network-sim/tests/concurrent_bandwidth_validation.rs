@@ -37,64 +37,61 @@ async fn measure_link_throughput(
     let tx_ip = link.cfg.tx_ip_cidr.split('/').next().unwrap().to_string();
     let port = link.port;
 
-    let recv_handle = std::thread::spawn(move || -> std::io::Result<u64> {
-        // Enter RX namespace
-        let ns = network_sim::Namespace::from_existing(rx_ns.clone());
-        let _ns_guard = ns.enter()?; // keep guard alive for thread lifetime
-                                     // Bind to all addresses to avoid any address-specific issues
-        let addr = format!("0.0.0.0:{}", port);
-        let socket = UdpSocket::bind(addr).map_err(|e| std::io::Error::other(e.to_string()))?;
-        socket
-            .set_read_timeout(Some(Duration::from_millis(100)))
-            .ok();
-
-        let end = Instant::now() + Duration::from_secs(duration_secs);
-        let mut total: u64 = 0;
-        let mut buf = [0u8; 65536];
-        while Instant::now() < end {
-            match socket.recv(&mut buf) {
-                Ok(n) => total += n as u64,
-                Err(ref e)
-                    if e.kind() == std::io::ErrorKind::WouldBlock
-                        || e.kind() == std::io::ErrorKind::TimedOut => {}
-                Err(e) => return Err(e),
+    // Namespace entry happens on dedicated threads; the executor is never blocked
+    let rx = Namespace::from_existing(rx_ns);
+    let recv_task = tokio::spawn(async move {
+        rx.run_blocking(move || -> std::io::Result<u64> {
+            // Bind to all addresses to avoid any address-specific issues
+            let addr = format!("0.0.0.0:{}", port);
+            let socket = UdpSocket::bind(addr)?;
+            socket
+                .set_read_timeout(Some(Duration::from_millis(100)))
+                .ok();
+
+            let end = Instant::now() + Duration::from_secs(duration_secs);
+            let mut total: u64 = 0;
+            let mut buf = [0u8; 65536];
+            while Instant::now() < end {
+                match socket.recv(&mut buf) {
+                    Ok(n) => total += n as u64,
+                    Err(ref e)
+                        if e.kind() == std::io::ErrorKind::WouldBlock
+                            || e.kind() == std::io::ErrorKind::TimedOut => {}
+                    Err(e) => return Err(e),
+                }
             }
-        }
-        Ok(total)
+            Ok(total)
+        })
+        .await
     });
 
     // small delay to ensure receiver bound
     tokio::time::sleep(Duration::from_millis(350)).await;
 
-    let send_handle = std::thread::spawn(move || -> std::io::Result<()> {
-        let ns = network_sim::Namespace::from_existing(tx_ns.clone());
-        let _ns_guard = ns.enter()?; // keep guard alive for thread lifetime
-                                     // bind to tx_ip to ensure correct source
-        let bind_addr = format!("{}:0", tx_ip);
-        let socket =
-            UdpSocket::bind(bind_addr).map_err(|e| std::io::Error::other(e.to_string()))?;
-        socket
-            .set_write_timeout(Some(Duration::from_millis(100)))
-            .ok();
-        let dest = format!("{}:{}", rx_ip_tx, port);
-        let payload = vec![0x58u8; 1200]; // 'X' * 1200
-        let end = Instant::now() + Duration::from_secs(duration_secs);
-        while Instant::now() < end {
-            let _ = socket.send_to(&payload, &dest);
-            // tiny pause; rate limiter should do the limiting
-            std::thread::sleep(Duration::from_micros(100));
-        }
-        Ok(())
-    });
+    Namespace::from_existing(tx_ns)
+        .run_blocking(move || -> std::io::Result<()> {
+            // bind to tx_ip to ensure correct source
+            let bind_addr = format!("{}:0", tx_ip);
+            let socket = UdpSocket::bind(bind_addr)?;
+            socket
+                .set_write_timeout(Some(Duration::from_millis(100)))
+                .ok();
+            let dest = format!("{}:{}", rx_ip_tx, port);
+            let payload = vec![0x58u8; 1200]; // 'X' * 1200
+            let end = Instant::now() + Duration::from_secs(duration_secs);
+            while Instant::now() < end {
+                let _ = socket.send_to(&payload, &dest);
+                // tiny pause; rate limiter should do the limiting
+                std::thread::sleep(Duration::from_micros(100));
+            }
+            Ok(())
+        })
+        .await?;
 
-    // Wait for sender to finish
-    let _ = send_handle
-        .join()
-        .map_err(|_| std::io::Error::other("send thread panicked"))?;
     // Collect receiver bytes
-    let total_bytes = recv_handle
-        .join()
-        .map_err(|_| std::io::Error::other("recv thread panicked"))?? as f64;
+    let total_bytes = recv_task
+        .await
+        .map_err(|_| std::io::Error::other("recv task panicked"))?? as f64;
 
     let kbps = (total_bytes * 8.0) / (duration_secs as f64 * 1000.0);
     Ok(kbps)
@@ -160,6 +157,7 @@ async fn test_bandwidth_sequential_validation() {
                     reorder_pct: 0.0,
                     duplicate_pct: 0.0,
                     loss_corr_pct: 0.0,
+                    port_filter: Vec::new(),
                 }),
             },
             target_kbps: rate,
@@ -327,6 +325,7 @@ async fn test_bandwidth_parallel_validation() {
                     reorder_pct: 0.0,
                     duplicate_pct: 0.0,
                     loss_corr_pct: 0.0,
+                    port_filter: Vec::new(),
                 }),
             },
             target_kbps: rate,
@@ -93,6 +93,60 @@ mod docker_tests {
         let _ = env.cleanup().await;
     }
 
+    /// Force-removes the container on drop so a failed assertion can't leak it
+    struct ContainerGuard(String);
+
+    impl Drop for ContainerGuard {
+        fn drop(&mut self) {
+            let _ = std::process::Command::new("docker")
+                .args(["rm", "-f", &self.0])
+                .output();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shape_container_link_attaches_to_peer() {
+        use network_sim::docker::shape_container_link;
+        use network_sim::qdisc::QdiscManager;
+        use network_sim::NetworkParams;
+
+        let qdisc = QdiscManager::new();
+        if !qdisc.has_net_admin().await {
+            eprintln!("Skipping container shaping test: requires NET_ADMIN");
+            return;
+        }
+
+        let run = tokio::process::Command::new("docker")
+            .args(["run", "-d", "--rm", "busybox", "sleep", "60"])
+            .output()
+            .await;
+        let container = match run {
+            Ok(out) if out.status.success() => {
+                ContainerGuard(String::from_utf8_lossy(&out.stdout).trim().to_string())
+            }
+            _ => {
+                eprintln!("Skipping container shaping test: docker unavailable");
+                return;
+            }
+        };
+
+        let params = NetworkParams::typical();
+        let link = shape_container_link(&qdisc, &container.0, &params)
+            .await
+            .expect("shape container link");
+        let state = link.stats(&qdisc).await.expect("qdisc state");
+        let root = state.root().expect("root qdisc on host peer");
+        assert_eq!(root.kind, "netem");
+        assert_eq!(root.delay_us, Some(params.delay_ms * 1000));
+
+        link.cleanup(&qdisc).await.expect("cleanup");
+        let state = qdisc
+            .get_qdisc_state(&link.host_interface)
+            .await
+            .expect("qdisc state");
+        assert!(state.find("netem").is_none());
+    }
+
     #[tokio::test]
     async fn test_multiple_namespace_operations() {
         let mut env = DockerNetworkEnv::new();
@@ -162,6 +162,7 @@ async fn test_four_links_udp_flow_with_ingress() {
             reorder_pct: 0.0,
             duplicate_pct: 0.0,
             loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
         };
         let guard = LinkGuard::new(&qdisc, &l.tx, &l.rx, &l.tx_ip, &l.rx_ip, &params)
             .await
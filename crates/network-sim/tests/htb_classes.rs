@@ -0,0 +1,199 @@
+//! Validate hierarchical HTB shaping: two UDP flows steered by destination port
+//! into classes with different rates must each respect their class limit, and
+//! a class may borrow up to its ceil while its sibling is idle.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{
+    apply_htb_params, get_qdisc_state, remove_network_params, udp_socket_in, HtbClass, HtbConfig,
+};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+const FAST_PORT: u16 = 9101;
+const SLOW_PORT: u16 = 9102;
+
+fn receive_bytes(ns: &str, port: u16, secs: u64) -> std::io::Result<u64> {
+    let socket = udp_socket_in(ns, ("0.0.0.0", port))?;
+    socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+    let end = Instant::now() + Duration::from_secs(secs);
+    let mut total = 0u64;
+    let mut buf = [0u8; 2048];
+    while Instant::now() < end {
+        if let Ok(n) = socket.recv(&mut buf) {
+            total += n as u64;
+        }
+    }
+    Ok(total)
+}
+
+/// Offer ~4 Mbps to `dst:port` from a socket and thread of its own, so a
+/// backlogged class never holds back another flow's sends
+fn flood(
+    src_ip: &'static str,
+    dst_ip: &'static str,
+    port: u16,
+    secs: u64,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let socket = UdpSocket::bind((src_ip, 0)).expect("bind tx");
+        let payload = vec![0x5Au8; 1000];
+        let end = Instant::now() + Duration::from_secs(secs);
+        while Instant::now() < end {
+            let _ = socket.send_to(&payload, (dst_ip, port));
+            std::thread::sleep(Duration::from_micros(2000));
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_htb_per_class_rate_limits() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping HTB test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+
+        let rx_ns = "nsim_htb_rx";
+        let pair = match VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_htb_tx".to_string(),
+                rx_if: "veth_htb_rx".to_string(),
+                tx_ip_cidr: "10.80.0.1/30".to_string(),
+                rx_ip_cidr: "10.80.0.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: Some(rx_ns.to_string()),
+                params: None,
+            },
+        )
+        .await
+        {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!("Skipping HTB test: veth setup failed: {}", e);
+                return;
+            }
+        };
+
+        let config = HtbConfig::new()
+            .class(HtbClass::new(2_000).ports(&[FAST_PORT]))
+            .class(HtbClass::new(500).ports(&[SLOW_PORT]))
+            .class(HtbClass::new(100))
+            .default_class(2);
+        apply_htb_params(&qdisc, "veth_htb_tx", &config)
+            .await
+            .expect("apply htb");
+
+        let secs = 4u64;
+        let receivers: Vec<_> = [FAST_PORT, SLOW_PORT]
+            .into_iter()
+            .map(|port| std::thread::spawn(move || receive_bytes(rx_ns, port, secs + 1)))
+            .collect();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Offer ~4 Mbps to each port, well above both class rates
+        let senders =
+            [FAST_PORT, SLOW_PORT].map(|port| flood("10.80.0.1", "10.80.0.2", port, secs));
+        for sender in senders {
+            sender.join().expect("sender");
+        }
+
+        let received: Vec<u64> = receivers
+            .into_iter()
+            .map(|h| h.join().expect("join").expect("receiver"))
+            .collect();
+        let state = get_qdisc_state(&qdisc, "veth_htb_tx").await.expect("state");
+
+        remove_network_params(&qdisc, "veth_htb_tx").await.ok();
+        pair.delete().await.ok();
+
+        let kbps: Vec<f64> = received
+            .iter()
+            .map(|b| (*b as f64 * 8.0) / (secs as f64 * 1000.0))
+            .collect();
+        println!("fast={:.0} kbps slow={:.0} kbps", kbps[0], kbps[1]);
+
+        assert!(kbps[0] <= 2_000.0 * 1.15, "fast class exceeded its rate");
+        assert!(kbps[0] >= 2_000.0 * 0.7, "fast class starved");
+        assert!(kbps[1] <= 500.0 * 1.15, "slow class exceeded its rate");
+        assert!(kbps[1] >= 500.0 * 0.7, "slow class starved");
+
+        assert_eq!(state.root().map(|q| q.kind.as_str()), Some("htb"));
+        let parent = state.class("1:1").expect("parent class");
+        assert_eq!(parent.rate_bps, Some(2_600_000));
+        let fast = state.class("1:2").expect("fast class stats");
+        let slow = state.class("1:3").expect("slow class stats");
+        assert_eq!(fast.parent.as_deref(), Some("1:1"));
+        assert!(fast.stats.sent_bytes > slow.stats.sent_bytes);
+        assert!(slow.stats.sent_bytes > 0);
+    }
+}
+
+#[tokio::test]
+async fn test_htb_class_borrows_up_to_ceil() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping HTB borrow test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+
+        let rx_ns = "nsim_htbb_rx";
+        let pair = match VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_htbb_tx".to_string(),
+                rx_if: "veth_htbb_rx".to_string(),
+                tx_ip_cidr: "10.80.1.1/30".to_string(),
+                rx_ip_cidr: "10.80.1.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: Some(rx_ns.to_string()),
+                params: None,
+            },
+        )
+        .await
+        {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!("Skipping HTB borrow test: veth setup failed: {}", e);
+                return;
+            }
+        };
+
+        // The busy class is guaranteed 500 kbps but may borrow up to 2 Mbps;
+        // its sibling (SLOW_PORT) stays idle
+        let config = HtbConfig::new()
+            .class(HtbClass::new(500).ceil(2_000).ports(&[FAST_PORT]))
+            .class(HtbClass::new(1_500).ports(&[SLOW_PORT]))
+            .default_class(1);
+        apply_htb_params(&qdisc, "veth_htbb_tx", &config)
+            .await
+            .expect("apply htb");
+
+        let secs = 4u64;
+        let receiver = std::thread::spawn(move || receive_bytes(rx_ns, FAST_PORT, secs + 1));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Offer ~4 Mbps, above the ceil
+        flood("10.80.1.1", "10.80.1.2", FAST_PORT, secs)
+            .join()
+            .expect("sender");
+        let received = receiver.join().expect("join").expect("receiver");
+
+        remove_network_params(&qdisc, "veth_htbb_tx").await.ok();
+        pair.delete().await.ok();
+
+        let kbps = (received as f64 * 8.0) / (secs as f64 * 1000.0);
+        println!("borrowing class={:.0} kbps", kbps);
+
+        assert!(kbps >= 500.0 * 2.0, "class did not borrow above its rate");
+        assert!(kbps <= 2_000.0 * 1.15, "class exceeded its ceil");
+    }
+}
@@ -0,0 +1,89 @@
+//! Verify that index-based APIs keep working after an interface is renamed.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{
+    apply_network_params_ifindex, create_shaped_veth_pair, get_qdisc_state, interface_index,
+    interface_name, remove_network_params_ifindex, NetworkParams, ShapedVethConfig,
+};
+
+fn ip(args: &[&str]) -> bool {
+    std::process::Command::new("ip")
+        .args(args)
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+#[tokio::test]
+async fn test_ifindex_updates_survive_rename() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping ifindex rename test: requires NET_ADMIN");
+        return;
+    }
+
+    let config = ShapedVethConfig {
+        tx_interface: "veth_idx_tx".to_string(),
+        rx_interface: "veth_idx_rx".to_string(),
+        tx_ip: "10.84.0.1/30".to_string(),
+        rx_ip: "10.84.0.2/30".to_string(),
+        rx_namespace: Some("nsim_idx_rx".to_string()),
+        network_params: NetworkParams::good(),
+        tx_ifindex: None,
+        rx_ifindex: None,
+    };
+    let link = create_shaped_veth_pair(&qdisc, &config)
+        .await
+        .expect("veth create");
+
+    let tx_index = link.tx_ifindex.expect("tx index recorded");
+    let rx_index = link.rx_ifindex.expect("rx index recorded");
+    assert_eq!(
+        interface_index("veth_idx_rx", Some("nsim_idx_rx"))
+            .await
+            .unwrap(),
+        rx_index
+    );
+
+    // Rename the TX end behind the library's back
+    assert!(ip(&["link", "set", "dev", "veth_idx_tx", "down"]));
+    let rename = ["link", "set", "dev", "veth_idx_tx", "name", "veth_idx_mv"];
+    assert!(ip(&rename));
+    assert!(ip(&["link", "set", "dev", "veth_idx_mv", "up"]));
+    assert_eq!(interface_name(tx_index, None).await.unwrap(), "veth_idx_mv");
+
+    apply_network_params_ifindex(&qdisc, tx_index, None, &NetworkParams::poor())
+        .await
+        .expect("apply by index");
+    let state = get_qdisc_state(&qdisc, "veth_idx_mv")
+        .await
+        .expect("qdisc state");
+    let netem = state.find("netem").expect("netem on renamed interface");
+    assert_eq!(netem.delay_us, Some(NetworkParams::poor().delay_ms * 1000));
+
+    // The RX index only exists inside its namespace; shape it there
+    link.apply_rx_params(&qdisc, &NetworkParams::typical())
+        .await
+        .expect("apply rx by index");
+    let state = qdisc
+        .get_qdisc_state_in_ns("nsim_idx_rx", "veth_idx_rx")
+        .await
+        .expect("rx qdisc state");
+    let netem = state.find("netem").expect("netem on rx interface");
+    assert_eq!(
+        netem.delay_us,
+        Some(NetworkParams::typical().delay_ms * 1000)
+    );
+    remove_network_params_ifindex(&qdisc, rx_index, Some("nsim_idx_rx"))
+        .await
+        .expect("remove rx by index");
+    let state = qdisc
+        .get_qdisc_state_in_ns("nsim_idx_rx", "veth_idx_rx")
+        .await
+        .expect("rx qdisc state");
+    assert!(state.find("netem").is_none());
+
+    // Cleanup resolves the renamed interface through its index
+    link.cleanup(&qdisc).await.expect("cleanup");
+    assert!(interface_name(tx_index, None).await.is_err());
+}
@@ -0,0 +1,112 @@
+//! Verify that updating parameters changes netem in place without an impairment gap.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{get_qdisc_state, update_network_params, NetworkParams};
+
+#[tokio::test]
+async fn test_update_in_place_has_no_gap() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping in-place update test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+        use tokio::process::Command;
+        use tokio::time::{sleep, Duration};
+
+        let params = |delay_ms| NetworkParams {
+            delay_ms,
+            loss_pct: 0.0,
+            rate_kbps: 0,
+            jitter_ms: 0,
+            reorder_pct: 0.0,
+            duplicate_pct: 0.0,
+            loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
+        };
+
+        let pair = VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_upd_tx".to_string(),
+                rx_if: "veth_upd_rx".to_string(),
+                tx_ip_cidr: "10.78.0.1/30".to_string(),
+                rx_ip_cidr: "10.78.0.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: Some("nsim_upd_rx".to_string()),
+                params: Some(params(40)),
+            },
+        )
+        .await
+        .expect("veth create");
+
+        // First update after creation must be in place
+        let in_place = update_network_params(&qdisc, "veth_upd_tx", &params(40))
+            .await
+            .expect("update");
+        assert!(in_place, "expected in-place change of existing netem");
+
+        // Continuous ping while the delay is changed mid-flight
+        let ping = tokio::spawn(async {
+            Command::new("ping")
+                .args(["-n", "-i", "0.02", "-c", "150", "-W", "1", "10.78.0.2"])
+                .output()
+                .await
+        });
+
+        sleep(Duration::from_secs(1)).await;
+        let in_place = update_network_params(&qdisc, "veth_upd_tx", &params(60))
+            .await
+            .expect("update");
+        assert!(in_place);
+
+        let out = ping.await.expect("join").expect("ping");
+        let stdout = String::from_utf8_lossy(&out.stdout);
+
+        let rtts: Vec<f64> = stdout
+            .lines()
+            .filter_map(|l| l.split("time=").nth(1))
+            .filter_map(|t| t.split_whitespace().next())
+            .filter_map(|v| v.parse::<f64>().ok())
+            .collect();
+
+        // Limited -> unlimited must clear the rate, not keep the old limit
+        let limited = NetworkParams {
+            rate_kbps: 800,
+            ..params(60)
+        };
+        assert!(update_network_params(&qdisc, "veth_upd_tx", &limited)
+            .await
+            .expect("update"));
+        let state = get_qdisc_state(&qdisc, "veth_upd_tx")
+            .await
+            .expect("qdisc state");
+        assert_eq!(state.find("netem").and_then(|n| n.rate_bps), Some(800_000));
+
+        assert!(update_network_params(&qdisc, "veth_upd_tx", &params(60))
+            .await
+            .expect("update"));
+        let state = get_qdisc_state(&qdisc, "veth_upd_tx")
+            .await
+            .expect("qdisc state");
+        let netem = state.find("netem").expect("netem still installed");
+        assert_eq!(netem.rate_bps, None, "rate limit survived the update");
+        assert_eq!(netem.delay_us, Some(60_000));
+
+        pair.clear(&qdisc).await.ok();
+        pair.delete().await.ok();
+
+        assert!(rtts.len() >= 145, "too many lost pings: {}", rtts.len());
+        let min_rtt = rtts.iter().cloned().fold(f64::INFINITY, f64::min);
+        println!("pings={} min_rtt={:.2}ms", rtts.len(), min_rtt);
+        // A delete/re-add would let some pings through unimpaired (sub-ms RTT)
+        assert!(
+            min_rtt >= 35.0,
+            "observed unimpaired ping ({:.2}ms) during update",
+            min_rtt
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Verify the userspace loopback proxy adds the configured latency (no privileges needed).
+
+use network_sim::{loopback, NetworkParams};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+
+async fn spawn_echo() -> std::net::SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.expect("bind echo");
+    let addr = socket.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 1500];
+        while let Ok((n, from)) = socket.recv_from(&mut buf).await {
+            let _ = socket.send_to(&buf[..n], from).await;
+        }
+    });
+    addr
+}
+
+async fn median_rtt_ms(target: std::net::SocketAddr, count: usize) -> f64 {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.expect("bind client");
+    let mut buf = [0u8; 64];
+    let mut rtts = Vec::with_capacity(count);
+    for i in 0..count {
+        let start = Instant::now();
+        socket
+            .send_to(&(i as u32).to_be_bytes(), target)
+            .await
+            .expect("send");
+        if tokio::time::timeout(Duration::from_secs(1), socket.recv(&mut buf))
+            .await
+            .is_ok()
+        {
+            rtts.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+    assert!(!rtts.is_empty(), "no echo replies from {}", target);
+    rtts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    rtts[rtts.len() / 2]
+}
+
+#[tokio::test]
+async fn test_loopback_proxy_adds_rtt() {
+    let echo = spawn_echo().await;
+    let direct = median_rtt_ms(echo, 10).await;
+
+    let params = NetworkParams {
+        delay_ms: 25,
+        ..Default::default()
+    };
+    let proxy = loopback::impair(&params, echo).await.expect("proxy");
+    let proxied = median_rtt_ms(proxy.addr(), 10).await;
+
+    // Delay applies in both directions
+    assert!(
+        proxied >= 50.0,
+        "proxied RTT {:.1} ms should include 2 x 25 ms",
+        proxied
+    );
+    assert!(proxied < direct + 80.0, "proxied RTT {:.1} ms", proxied);
+}
+
+#[tokio::test]
+async fn test_loopback_proxy_total_loss_blocks_traffic() {
+    let echo = spawn_echo().await;
+    let params = NetworkParams {
+        loss_pct: 1.0,
+        ..Default::default()
+    };
+    let proxy = loopback::impair(&params, echo).await.expect("proxy");
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    socket.send_to(b"lost", proxy.addr()).await.unwrap();
+    let mut buf = [0u8; 16];
+    let reply = tokio::time::timeout(Duration::from_millis(200), socket.recv(&mut buf)).await;
+    assert!(reply.is_err(), "datagram should have been dropped");
+}
+
+#[tokio::test]
+async fn test_loopback_proxy_rejects_invalid_params() {
+    let echo = spawn_echo().await;
+    let params = NetworkParams {
+        loss_pct: 1.5,
+        ..Default::default()
+    };
+    let err = loopback::impair(&params, echo)
+        .await
+        .expect_err("out-of-range loss must be rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
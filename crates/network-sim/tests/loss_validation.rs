@@ -44,6 +44,7 @@ async fn test_loss_enforcement_udp() {
                     reorder_pct: 0.0,
                     duplicate_pct: 0.0,
                     loss_corr_pct: 0.0,
+                    port_filter: Vec::new(),
                 }),
             },
         )
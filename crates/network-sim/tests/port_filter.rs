@@ -0,0 +1,169 @@
+//! Validate per-port impairment: only the filtered destination port sees netem delay.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{apply_network_params, remove_network_params, udp_socket_in, NetworkParams};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+const IMPAIRED_PORT: u16 = 9201;
+const CLEAN_PORT: u16 = 9202;
+
+/// Echo datagrams back to the sender from inside the namespace
+fn echo_server(ns: &str, port: u16, secs: u64) -> std::io::Result<()> {
+    let socket = udp_socket_in(ns, ("0.0.0.0", port))?;
+    socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+    let end = Instant::now() + Duration::from_secs(secs);
+    let mut buf = [0u8; 256];
+    while Instant::now() < end {
+        if let Ok((n, from)) = socket.recv_from(&mut buf) {
+            let _ = socket.send_to(&buf[..n], from);
+        }
+    }
+    Ok(())
+}
+
+/// Median round-trip time in milliseconds over `count` probes
+fn median_rtt_ms(port: u16, count: usize) -> f64 {
+    let socket = UdpSocket::bind("10.81.0.1:0").expect("bind client");
+    socket
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+    let mut rtts = Vec::with_capacity(count);
+    let mut buf = [0u8; 256];
+    for i in 0..count {
+        let start = Instant::now();
+        socket
+            .send_to(&(i as u32).to_be_bytes(), ("10.81.0.2", port))
+            .expect("send");
+        if socket.recv(&mut buf).is_ok() {
+            rtts.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!rtts.is_empty(), "no echo replies on port {}", port);
+    rtts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    rtts[rtts.len() / 2]
+}
+
+#[tokio::test]
+async fn test_port_filter_only_delays_matching_port() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping port filter test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+
+        let rx_ns = "nsim_pf_rx";
+        let pair = match VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_pf_tx".to_string(),
+                rx_if: "veth_pf_rx".to_string(),
+                tx_ip_cidr: "10.81.0.1/30".to_string(),
+                rx_ip_cidr: "10.81.0.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: Some(rx_ns.to_string()),
+                params: None,
+            },
+        )
+        .await
+        {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!("Skipping port filter test: veth setup failed: {}", e);
+                return;
+            }
+        };
+
+        let params = NetworkParams {
+            delay_ms: 80,
+            ..Default::default()
+        }
+        .port_filter(&[IMPAIRED_PORT]);
+        apply_network_params(&qdisc, "veth_pf_tx", &params)
+            .await
+            .expect("apply filtered params");
+
+        let servers: Vec<_> = [IMPAIRED_PORT, CLEAN_PORT]
+            .into_iter()
+            .map(|port| std::thread::spawn(move || echo_server(rx_ns, port, 6)))
+            .collect();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let (impaired, clean) = tokio::task::spawn_blocking(|| {
+            (
+                median_rtt_ms(IMPAIRED_PORT, 20),
+                median_rtt_ms(CLEAN_PORT, 20),
+            )
+        })
+        .await
+        .expect("probe");
+
+        for s in servers {
+            let _ = s.join();
+        }
+        remove_network_params(&qdisc, "veth_pf_tx").await.ok();
+        pair.delete().await.ok();
+
+        println!("impaired={:.1}ms clean={:.1}ms", impaired, clean);
+        assert!(impaired >= 75.0, "filtered port was not delayed");
+        assert!(clean < 20.0, "unfiltered port was delayed");
+    }
+}
+
+#[tokio::test]
+async fn test_port_filter_applied_inside_tx_namespace() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping namespaced port filter test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+
+        let tx_ns = "nsim_pfns_tx";
+        let pair = match VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_pfns_tx".to_string(),
+                rx_if: "veth_pfns_rx".to_string(),
+                tx_ip_cidr: "10.81.1.1/30".to_string(),
+                rx_ip_cidr: "10.81.1.2/30".to_string(),
+                tx_ns: Some(tx_ns.to_string()),
+                rx_ns: Some("nsim_pfns_rx".to_string()),
+                params: Some(NetworkParams {
+                    delay_ms: 40,
+                    port_filter: vec![IMPAIRED_PORT],
+                    ..Default::default()
+                }),
+            },
+        )
+        .await
+        {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!(
+                    "Skipping namespaced port filter test: veth setup failed: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let state = qdisc.get_qdisc_state_in_ns(tx_ns, "veth_pfns_tx").await;
+        pair.delete().await.ok();
+
+        // The filter tree, not a plain root netem, must be installed in the namespace
+        let state = state.expect("qdisc state in namespace");
+        assert_eq!(state.root().map(|q| q.kind.as_str()), Some("prio"));
+        let netem = state.find("netem").expect("netem band");
+        assert_eq!(netem.parent.as_deref(), Some("1:1"));
+        assert_eq!(netem.delay_us, Some(40_000));
+    }
+}
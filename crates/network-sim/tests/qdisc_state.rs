@@ -0,0 +1,63 @@
+//! Verify that qdisc state read back from the kernel matches applied parameters.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{apply_network_params, get_qdisc_state, remove_network_params, NetworkParams};
+
+#[tokio::test]
+async fn test_qdisc_state_matches_applied_params() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping qdisc state test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+
+        let pair = VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_qs_tx".to_string(),
+                rx_if: "veth_qs_rx".to_string(),
+                tx_ip_cidr: "10.79.0.1/30".to_string(),
+                rx_ip_cidr: "10.79.0.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: None,
+                params: None,
+            },
+        )
+        .await
+        .expect("veth create");
+
+        let params = NetworkParams {
+            delay_ms: 40,
+            loss_pct: 0.02,
+            rate_kbps: 3_000,
+            jitter_ms: 5,
+            reorder_pct: 0.0,
+            duplicate_pct: 0.0,
+            loss_corr_pct: 0.0,
+            port_filter: Vec::new(),
+        };
+        apply_network_params(&qdisc, "veth_qs_tx", &params)
+            .await
+            .expect("apply");
+
+        let state = get_qdisc_state(&qdisc, "veth_qs_tx")
+            .await
+            .expect("qdisc state");
+        println!("{:?}", state);
+
+        let root = state.root().expect("root qdisc").clone();
+        remove_network_params(&qdisc, "veth_qs_tx").await.ok();
+        pair.delete().await.ok();
+
+        assert_eq!(root.kind, "netem");
+        assert_eq!(root.delay_us, Some(40_000));
+        assert_eq!(root.jitter_us, Some(5_000));
+        let loss = root.loss_percent.expect("loss reported");
+        assert!((loss - 2.0).abs() < 0.01, "loss {}", loss);
+        assert_eq!(root.rate_bps, Some(3_000_000));
+    }
+}
@@ -0,0 +1,107 @@
+//! Verify that guards tear down veth pairs and qdiscs when a test panics.
+
+use network_sim::qdisc::QdiscManager;
+use network_sim::{
+    apply_network_params_guarded, create_shaped_veth_pair, NetworkParams, ShapedVethConfig,
+};
+
+fn link_exists(interface: &str) -> bool {
+    std::process::Command::new("ip")
+        .args(["link", "show", "dev", interface])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn netns_exists(ns: &str) -> bool {
+    std::path::Path::new("/var/run/netns").join(ns).exists()
+}
+
+#[tokio::test]
+async fn test_veth_guard_cleans_up_on_panic() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping RAII guard test: requires NET_ADMIN");
+        return;
+    }
+
+    let config = ShapedVethConfig {
+        tx_interface: "veth_grd_tx".to_string(),
+        rx_interface: "veth_grd_rx".to_string(),
+        tx_ip: "10.82.0.1/30".to_string(),
+        rx_ip: "10.82.0.2/30".to_string(),
+        rx_namespace: Some("nsim_grd_rx".to_string()),
+        network_params: NetworkParams::good(),
+        tx_ifindex: None,
+        rx_ifindex: None,
+    };
+
+    let task_config = config.clone();
+    let result = tokio::spawn(async move {
+        let qdisc = QdiscManager::new();
+        let _guard = create_shaped_veth_pair(&qdisc, &task_config)
+            .await
+            .expect("veth create");
+        assert!(link_exists(&task_config.tx_interface));
+        panic!("deliberate panic with a live guard");
+    })
+    .await;
+    assert!(result.unwrap_err().is_panic());
+
+    assert!(
+        !link_exists(&config.tx_interface),
+        "tx interface should be removed by the guard"
+    );
+    assert!(
+        !netns_exists(config.rx_namespace.as_deref().unwrap()),
+        "rx namespace should be removed by the guard"
+    );
+}
+
+#[tokio::test]
+async fn test_qdisc_guard_cleans_up_on_panic() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping RAII guard test: requires NET_ADMIN");
+        return;
+    }
+
+    let config = ShapedVethConfig {
+        tx_interface: "veth_grq_tx".to_string(),
+        rx_interface: "veth_grq_rx".to_string(),
+        tx_ip: "10.82.0.5/30".to_string(),
+        rx_ip: "10.82.0.6/30".to_string(),
+        rx_namespace: None,
+        network_params: NetworkParams::good(),
+        tx_ifindex: None,
+        rx_ifindex: None,
+    };
+    let link = create_shaped_veth_pair(&qdisc, &config)
+        .await
+        .expect("veth create");
+
+    let rx_if = config.rx_interface.clone();
+    let result = tokio::spawn(async move {
+        let qdisc = QdiscManager::new();
+        let _guard = apply_network_params_guarded(&qdisc, &rx_if, &NetworkParams::poor())
+            .await
+            .expect("apply params");
+        panic!("deliberate panic with a live qdisc guard");
+    })
+    .await;
+    assert!(result.unwrap_err().is_panic());
+
+    let out = std::process::Command::new("tc")
+        .args(["qdisc", "show", "dev", &config.rx_interface])
+        .output()
+        .expect("tc qdisc show");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        !stdout.contains("netem"),
+        "netem should be removed by the guard: {}",
+        stdout
+    );
+
+    link.cleanup(&qdisc).await.expect("cleanup");
+    assert!(!link_exists(&config.tx_interface));
+}
@@ -5,8 +5,7 @@
 //! asserts rates match configured values within tolerance.
 
 use network_sim::qdisc::QdiscManager;
-use network_sim::{Namespace, VethPair, VethPairConfig};
-use std::net::UdpSocket;
+use network_sim::{udp_socket_in, Namespace, VethPair, VethPairConfig};
 use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug)]
@@ -30,11 +29,8 @@ async fn measure_udp(cfg: &TestLinkCfg, secs: u64) -> std::io::Result<f64> {
     let port = cfg.port;
 
     // Receiver thread
+    let sock = udp_socket_in(&rx_ns, ("0.0.0.0", port))?;
     let recv_handle = std::thread::spawn(move || -> std::io::Result<u64> {
-        let ns = Namespace::from_existing(rx_ns);
-        let _guard = ns.enter()?;
-        let addr = format!("0.0.0.0:{}", port);
-        let sock = UdpSocket::bind(addr).map_err(|e| std::io::Error::other(e.to_string()))?;
         sock.set_read_timeout(Some(Duration::from_millis(100))).ok();
         let end = Instant::now() + Duration::from_secs(secs);
         let mut total = 0u64;
@@ -54,11 +50,8 @@ async fn measure_udp(cfg: &TestLinkCfg, secs: u64) -> std::io::Result<f64> {
     tokio::time::sleep(Duration::from_millis(300)).await;
 
     // Sender thread
+    let sock = udp_socket_in(&tx_ns, format!("{}:0", tx_ip))?;
     let send_handle = std::thread::spawn(move || -> std::io::Result<()> {
-        let ns = Namespace::from_existing(tx_ns);
-        let _guard = ns.enter()?;
-        let bind_addr = format!("{}:0", tx_ip);
-        let sock = UdpSocket::bind(bind_addr).map_err(|e| std::io::Error::other(e.to_string()))?;
         let dest = format!("{}:{}", rx_ip, port);
         let payload = vec![0x58u8; 1200];
         let end = Instant::now() + Duration::from_secs(secs);
@@ -118,6 +111,7 @@ async fn test_network_shaping_enforcement() {
                     reorder_pct: 0.0,
                     duplicate_pct: 0.0,
                     loss_corr_pct: 0.0,
+                    port_filter: Vec::new(),
                 }),
             },
             rate_kbps: rate,
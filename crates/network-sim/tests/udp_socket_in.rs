@@ -0,0 +1,84 @@
+//! Verify that sockets bound via the namespace socket factory carry traffic
+//! between the host and a namespace.
+
+use network_sim::qdisc::QdiscManager;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_udp_socket_in_namespace_roundtrip() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping namespace socket test: requires NET_ADMIN");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use network_sim::link::{VethPair, VethPairConfig};
+        use network_sim::{tokio_udp_socket_in, udp_socket_in, Namespace};
+
+        let ns = match Namespace::ensure("nsim_sock_rx").await {
+            Ok(ns) => ns,
+            Err(e) => {
+                eprintln!("Skipping namespace socket test: {}", e);
+                return;
+            }
+        };
+
+        let pair = VethPair::create(
+            &qdisc,
+            &VethPairConfig {
+                tx_if: "veth_sock_tx".to_string(),
+                rx_if: "veth_sock_rx".to_string(),
+                tx_ip_cidr: "10.83.0.1/30".to_string(),
+                rx_ip_cidr: "10.83.0.2/30".to_string(),
+                tx_ns: None,
+                rx_ns: Some(ns.name().to_string()),
+                params: None,
+            },
+        )
+        .await
+        .expect("veth create");
+
+        // Blocking variant: namespace socket echoes host datagrams
+        let ns_sock = udp_socket_in(ns.name(), "10.83.0.2:9301").expect("bind in ns");
+        ns_sock
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let host_sock = std::net::UdpSocket::bind("10.83.0.1:0").expect("bind host");
+        host_sock
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+
+        host_sock.send_to(b"ping", "10.83.0.2:9301").expect("send");
+        let mut buf = [0u8; 16];
+        let (n, from) = ns_sock.recv_from(&mut buf).expect("ns recv");
+        assert_eq!(&buf[..n], b"ping");
+        ns_sock.send_to(b"pong", from).expect("ns send");
+        let n = host_sock.recv(&mut buf).expect("host recv");
+        assert_eq!(&buf[..n], b"pong");
+
+        // The caller's thread must still be in the root namespace
+        assert!(
+            std::net::UdpSocket::bind("10.83.0.2:0").is_err(),
+            "namespace address should not be bindable from the host"
+        );
+
+        // Tokio variant
+        let ns_sock = tokio_udp_socket_in(ns.name(), "10.83.0.2:9302".parse().unwrap())
+            .await
+            .expect("tokio bind in ns");
+        let host_sock = tokio::net::UdpSocket::bind("10.83.0.1:0")
+            .await
+            .expect("tokio bind host");
+        host_sock.send_to(b"hello", "10.83.0.2:9302").await.unwrap();
+        let (n, _) = tokio::time::timeout(Duration::from_secs(2), ns_sock.recv_from(&mut buf))
+            .await
+            .expect("recv timeout")
+            .expect("recv");
+        assert_eq!(&buf[..n], b"hello");
+
+        let _ = pair.delete().await;
+        let _ = ns.delete().await;
+    }
+}
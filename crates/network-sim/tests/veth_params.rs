@@ -0,0 +1,70 @@
+//! Verify that `VethPair::create` checks shaping parameters the same way the
+//! other apply paths do.
+
+#![cfg(target_os = "linux")]
+
+use network_sim::link::{VethPair, VethPairConfig};
+use network_sim::qdisc::QdiscManager;
+use network_sim::NetworkParams;
+
+fn config(tx_if: &str, rx_if: &str, params: NetworkParams) -> VethPairConfig {
+    VethPairConfig {
+        tx_if: tx_if.to_string(),
+        rx_if: rx_if.to_string(),
+        tx_ip_cidr: "10.81.0.1/30".to_string(),
+        rx_ip_cidr: "10.81.0.2/30".to_string(),
+        tx_ns: None,
+        rx_ns: None,
+        params: Some(params),
+    }
+}
+
+#[tokio::test]
+async fn test_veth_create_rejects_invalid_params() {
+    let qdisc = QdiscManager::new();
+    let params = NetworkParams {
+        loss_pct: 1.5,
+        ..Default::default()
+    };
+    let err = VethPair::create(&qdisc, &config("veth_bad_tx", "veth_bad_rx", params))
+        .await
+        .expect_err("out-of-range loss must be rejected");
+    assert!(err.to_string().contains("Invalid parameters"), "{}", err);
+
+    // Validation happens before any link is created
+    let link = tokio::process::Command::new("ip")
+        .args(["link", "show", "veth_bad_tx"])
+        .output()
+        .await
+        .expect("ip");
+    assert!(!link.status.success());
+}
+
+#[tokio::test]
+async fn test_veth_create_reports_missing_netem() {
+    let qdisc = QdiscManager::new();
+    if !qdisc.has_net_admin().await {
+        eprintln!("Skipping missing netem test: requires NET_ADMIN");
+        return;
+    }
+    if qdisc.capabilities().await.has("netem") {
+        eprintln!("Skipping missing netem test: netem is available");
+        return;
+    }
+
+    let err = VethPair::create(
+        &qdisc,
+        &config("veth_nonm_tx", "veth_nonm_rx", NetworkParams::typical()),
+    )
+    .await
+    .expect_err("netem is unavailable");
+    let _ = tokio::process::Command::new("ip")
+        .args(["link", "del", "dev", "veth_nonm_tx"])
+        .output()
+        .await;
+    assert!(
+        err.to_string().contains("not available in this kernel"),
+        "{}",
+        err
+    );
+}
@@ -462,6 +462,7 @@ impl ElementImpl for DispatcherImpl {
         if let Some(pos) = srcpads.iter().position(|p| p == pad) {
             self.obj().remove_pad(&srcpads[pos]).ok();
             srcpads.remove(pos);
+            self.inner.failed_outputs.lock().remove(pad.name().as_str());
             let mut state = self.inner.state.lock();
             if pos < state.weights.len() {
                 state.weights.remove(pos);
@@ -611,36 +612,40 @@ impl DispatcherImpl {
                 } else {
                     false
                 };
-                if let Ok(flow) = outpad.push(buf.clone()) {
-                    if scheduler == Scheduler::Drr {
-                        let pkt_size = buf.size();
-                        let base_q = *inner.quantum_bytes.lock() as i64;
-                        let mut st2 = inner.state.lock();
-                        st2.orig_packets += 1;
-                        st2.last_buffer_time = std::time::Instant::now();
-                        if chosen_idx < st2.drr_deficits.len() {
-                            let new_def = st2.drr_deficits[chosen_idx] - pkt_size as i64;
-                            let floor = -4 * base_q;
-                            st2.drr_deficits[chosen_idx] = new_def.max(floor);
+                match outpad.push(buf.clone()) {
+                    Ok(flow) => {
+                        Self::clear_output_failure(inner, outpad);
+                        if scheduler == Scheduler::Drr {
+                            let pkt_size = buf.size();
+                            let base_q = *inner.quantum_bytes.lock() as i64;
+                            let mut st2 = inner.state.lock();
+                            st2.orig_packets += 1;
+                            st2.last_buffer_time = std::time::Instant::now();
+                            if chosen_idx < st2.drr_deficits.len() {
+                                let new_def = st2.drr_deficits[chosen_idx] - pkt_size as i64;
+                                let floor = -4 * base_q;
+                                st2.drr_deficits[chosen_idx] = new_def.max(floor);
+                            }
+                            let srcpads_len = srcpads.len();
+                            if srcpads_len > 0 {
+                                st2.drr_ptr = (chosen_idx + 1) % srcpads_len;
+                            }
+                        } else {
+                            let mut st2 = inner.state.lock();
+                            st2.orig_packets += 1;
+                            st2.last_buffer_time = std::time::Instant::now();
                         }
-                        let srcpads_len = srcpads.len();
-                        if srcpads_len > 0 {
-                            st2.drr_ptr = (chosen_idx + 1) % srcpads_len;
+                        if should_duplicate && can_dup && srcpads.len() > 1 {
+                            crate::dispatcher::duplication::duplicate_keyframe_to_backup(
+                                inner.as_ref(),
+                                &srcpads,
+                                chosen_idx,
+                                &buf,
+                            );
                         }
-                    } else {
-                        let mut st2 = inner.state.lock();
-                        st2.orig_packets += 1;
-                        st2.last_buffer_time = std::time::Instant::now();
+                        return Ok(flow);
                     }
-                    if should_duplicate && can_dup && srcpads.len() > 1 {
-                        crate::dispatcher::duplication::duplicate_keyframe_to_backup(
-                            inner.as_ref(),
-                            &srcpads,
-                            chosen_idx,
-                            &buf,
-                        );
-                    }
-                    return Ok(flow);
+                    Err(err) => Self::note_output_failure(inner, outpad, err),
                 }
             }
         }
@@ -650,6 +655,7 @@ impl DispatcherImpl {
                 if outpad.is_linked() {
                     match outpad.push(buf.clone()) {
                         Ok(flow) => {
+                            Self::clear_output_failure(inner, outpad);
                             if scheduler == Scheduler::Drr {
                                 let mut st = inner.state.lock();
                                 st.orig_packets += 1;
@@ -667,7 +673,7 @@ impl DispatcherImpl {
                             }
                             return Ok(flow);
                         }
-                        Err(_) => continue,
+                        Err(err) => Self::note_output_failure(inner, outpad, err),
                     }
                 }
             }
@@ -675,6 +681,38 @@ impl DispatcherImpl {
         Err(gst::FlowError::NotLinked)
     }
 
+    /// Warn once when an output starts refusing buffers; its traffic falls
+    /// through to the remaining outputs until it recovers. Flushing and EOS
+    /// are normal during seeks and teardown and are not reported.
+    fn note_output_failure(inner: &DispatcherInner, outpad: &gst::Pad, err: gst::FlowError) {
+        if !matches!(
+            err,
+            gst::FlowError::Error | gst::FlowError::NotLinked | gst::FlowError::NotNegotiated
+        ) {
+            return;
+        }
+        let name = outpad.name().to_string();
+        if !inner.failed_outputs.lock().insert(name.clone()) {
+            return;
+        }
+        gst::debug!(CAT, obj = outpad, "Output failed with {:?}", err);
+        if let Some(dispatcher) = outpad.parent().and_downcast::<Dispatcher>() {
+            gst::element_warning!(
+                dispatcher,
+                gst::StreamError::Failed,
+                ("Output {} failed", name),
+                ["Push returned {:?}; falling back to other outputs", err]
+            );
+        }
+    }
+
+    fn clear_output_failure(inner: &DispatcherInner, outpad: &gst::Pad) {
+        let mut failed = inner.failed_outputs.lock();
+        if !failed.is_empty() && failed.remove(outpad.name().as_str()) {
+            gst::info!(CAT, obj = outpad, "Output recovered");
+        }
+    }
+
     pub fn handle_sink_event(
         inner: &Arc<DispatcherInner>,
         pad: &gst::Pad,
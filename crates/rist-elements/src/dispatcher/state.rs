@@ -1,6 +1,7 @@
 use gst::glib;
 use gstreamer as gst;
 use parking_lot::Mutex;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct LinkStats {
@@ -137,6 +138,8 @@ pub struct DispatcherInner {
     pub min_burst_pkts: Mutex<u32>,
     pub use_switch_threshold: Mutex<bool>,
     pub flow_watchdog_id: Mutex<Option<glib::SourceId>>,
+    /// Src pads whose last push failed, so the warning is posted once
+    pub failed_outputs: Mutex<HashSet<String>>,
 }
 
 impl Default for DispatcherInner {
@@ -171,6 +174,7 @@ impl Default for DispatcherInner {
             min_burst_pkts: Mutex::new(12),
             use_switch_threshold: Mutex::new(false),
             flow_watchdog_id: Mutex::new(None),
+            failed_outputs: Mutex::new(HashSet::new()),
         }
     }
 }
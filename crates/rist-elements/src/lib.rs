@@ -14,7 +14,9 @@ pub use crate::dispatcher::Dispatcher;
 pub use crate::dynbitrate::DynBitrate;
 
 #[cfg(feature = "test-plugin")]
-pub use crate::test_harness::RistStatsMock;
+pub use crate::test_harness::{
+    CounterSink, RistSinkMock, RistStatsMock, SessionDelta, StatsMode, TimelineStep,
+};
 
 fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     dispatcher::register(plugin)?;
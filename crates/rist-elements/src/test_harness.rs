@@ -12,6 +12,7 @@ use gstreamer as gst;
 use once_cell::sync::Lazy;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Register all test harness elements
 pub fn register_test_elements() -> Result<()> {
@@ -20,24 +21,157 @@ pub fn register_test_elements() -> Result<()> {
     counter_sink::register()?;
     encoder_stub::register()?;
     riststats_mock::register()?;
+    rtp_seq_src::register()?;
+    rtp_seq_check::register()?;
+    ristsink_mock::register()?;
 
     Ok(())
 }
 
 // Re-export test elements
-pub use riststats_mock::RistStatsMock;
+pub use counter_sink::CounterSink;
+pub use ristsink_mock::RistSinkMock;
+pub use riststats_mock::{RistStatsMock, SessionDelta, StatsMode, TimelineStep};
 
 /// Counter sink: counts buffers and records EOS/FLUSH events
 /// Useful for verifying that the correct number of buffers flow through pipelines
 pub mod counter_sink {
     use super::*;
+    use std::collections::VecDeque;
+    use std::time::Instant;
+
+    static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+        gst::DebugCategory::new(
+            "counter-sink",
+            gst::DebugColorFlags::empty(),
+            Some("Buffer counting test sink"),
+        )
+    });
+
+    /// Number of arrival timestamps kept for `jitter-ms` and `get-arrival-times`
+    pub const ARRIVAL_HISTORY: usize = 4096;
 
     #[derive(Default)]
     pub struct Inner {
         count: AtomicU64,
+        bytes: AtomicU64,
         got_eos: AtomicU64,
         got_flush_start: AtomicU64,
         got_flush_stop: AtomicU64,
+        arrivals: Mutex<Arrivals>,
+        flow_return: Mutex<FlowMode>,
+        fail_after: AtomicU64,
+    }
+
+    impl Inner {
+        /// Flow error to return for the next buffer, if the sink is failing
+        fn failure(&self) -> Option<gst::FlowError> {
+            let mode = *self.flow_return.lock().unwrap();
+            let fail_after = self.fail_after.load(Ordering::Relaxed);
+            if fail_after == 0 {
+                return mode.as_error();
+            }
+            if self.count.load(Ordering::Relaxed) < fail_after {
+                return None;
+            }
+            Some(mode.as_error().unwrap_or(gst::FlowError::Error))
+        }
+    }
+
+    /// Result the chain function reports upstream
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    enum FlowMode {
+        #[default]
+        Ok,
+        NotLinked,
+        Error,
+        Flushing,
+    }
+
+    impl FlowMode {
+        fn as_str(self) -> &'static str {
+            match self {
+                FlowMode::Ok => "ok",
+                FlowMode::NotLinked => "not-linked",
+                FlowMode::Error => "error",
+                FlowMode::Flushing => "flushing",
+            }
+        }
+
+        fn from_str(s: &str) -> Option<Self> {
+            match s {
+                "ok" => Some(FlowMode::Ok),
+                "not-linked" => Some(FlowMode::NotLinked),
+                "error" => Some(FlowMode::Error),
+                "flushing" => Some(FlowMode::Flushing),
+                _ => None,
+            }
+        }
+
+        fn as_error(self) -> Option<gst::FlowError> {
+            match self {
+                FlowMode::Ok => None,
+                FlowMode::NotLinked => Some(gst::FlowError::NotLinked),
+                FlowMode::Error => Some(gst::FlowError::Error),
+                FlowMode::Flushing => Some(gst::FlowError::Flushing),
+            }
+        }
+    }
+
+    /// Buffer arrival times, in microseconds since the first buffer
+    #[derive(Default)]
+    struct Arrivals {
+        origin: Option<Instant>,
+        recent: VecDeque<u64>,
+        last_us: Option<u64>,
+        gap_count: u64,
+        gap_sum_us: u64,
+        gap_max_us: u64,
+    }
+
+    impl Arrivals {
+        fn record(&mut self, now: Instant) {
+            let origin = *self.origin.get_or_insert(now);
+            let at_us = now.duration_since(origin).as_micros() as u64;
+            if let Some(last_us) = self.last_us {
+                let gap = at_us.saturating_sub(last_us);
+                self.gap_count += 1;
+                self.gap_sum_us = self.gap_sum_us.saturating_add(gap);
+                self.gap_max_us = self.gap_max_us.max(gap);
+            }
+            self.last_us = Some(at_us);
+            if self.recent.len() == ARRIVAL_HISTORY {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(at_us);
+        }
+
+        fn max_gap_ms(&self) -> f64 {
+            self.gap_max_us as f64 / 1000.0
+        }
+
+        fn avg_gap_ms(&self) -> f64 {
+            if self.gap_count == 0 {
+                return 0.0;
+            }
+            self.gap_sum_us as f64 / self.gap_count as f64 / 1000.0
+        }
+
+        /// Mean absolute deviation of the recent inter-arrival gaps
+        fn jitter_ms(&self) -> f64 {
+            let gaps: Vec<f64> = self
+                .recent
+                .iter()
+                .zip(self.recent.iter().skip(1))
+                .map(|(a, b)| (b - a) as f64)
+                .collect();
+            if gaps.is_empty() {
+                return 0.0;
+            }
+            let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+            let dev = gaps.iter().map(|g| (g - mean).abs()).sum::<f64>() / gaps.len() as f64;
+            dev / 1000.0
+        }
     }
 
     glib::wrapper! {
@@ -72,8 +206,13 @@ pub mod counter_sink {
             let inner = self.inner.clone();
             let sinkpad = gst::Pad::builder_from_template(&sink_tmpl)
                 .name("sink")
-                .chain_function(move |_pad, _parent, _buf| {
+                .chain_function(move |_pad, _parent, buf| {
+                    if let Some(err) = inner.failure() {
+                        return Err(err);
+                    }
+                    inner.arrivals.lock().unwrap().record(Instant::now());
                     inner.count.fetch_add(1, Ordering::Relaxed);
+                    inner.bytes.fetch_add(buf.size() as u64, Ordering::Relaxed);
                     Ok(gst::FlowSuccess::Ok)
                 })
                 .event_function({
@@ -105,6 +244,42 @@ pub mod counter_sink {
                     glib::ParamSpecUInt64::builder("count")
                         .flags(glib::ParamFlags::READABLE)
                         .build(),
+                    glib::ParamSpecUInt64::builder("bytes")
+                        .nick("Bytes")
+                        .blurb("Total payload bytes received")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecDouble::builder("max-inter-arrival-ms")
+                        .nick("Max inter-arrival")
+                        .blurb("Largest gap between consecutive buffers in milliseconds")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecDouble::builder("avg-inter-arrival-ms")
+                        .nick("Average inter-arrival")
+                        .blurb("Mean gap between consecutive buffers in milliseconds")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecDouble::builder("jitter-ms")
+                        .nick("Jitter")
+                        .blurb(
+                            "Mean absolute deviation of recent inter-arrival gaps in milliseconds",
+                        )
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecString::builder("flow-return")
+                        .nick("Flow return")
+                        .blurb("Chain result: ok, not-linked, error or flushing")
+                        .default_value(Some("ok"))
+                        .flags(glib::ParamFlags::READABLE | glib::ParamFlags::WRITABLE)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("fail-after")
+                        .nick("Fail after")
+                        .blurb(
+                            "Accept this many buffers, then return flow-return \
+                             (error if ok); 0 applies flow-return immediately",
+                        )
+                        .flags(glib::ParamFlags::READABLE | glib::ParamFlags::WRITABLE)
+                        .build(),
                     glib::ParamSpecBoolean::builder("got-eos")
                         .flags(glib::ParamFlags::READABLE)
                         .build(),
@@ -119,9 +294,39 @@ pub mod counter_sink {
             PROPS.as_ref()
         }
 
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            match pspec.name() {
+                "flow-return" => {
+                    let requested = value.get::<Option<String>>().ok().flatten();
+                    match requested.as_deref().and_then(FlowMode::from_str) {
+                        Some(mode) => *self.inner.flow_return.lock().unwrap() = mode,
+                        None => {
+                            gst::warning!(CAT, imp = self, "Unknown flow return {:?}", requested)
+                        }
+                    }
+                }
+                "fail-after" => {
+                    if let Ok(n) = value.get::<u64>() {
+                        self.inner.fail_after.store(n, Ordering::Relaxed);
+                    }
+                }
+                _ => {}
+            }
+        }
+
         fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
             match pspec.name() {
                 "count" => self.inner.count.load(Ordering::Relaxed).to_value(),
+                "bytes" => self.inner.bytes.load(Ordering::Relaxed).to_value(),
+                "flow-return" => self.inner.flow_return.lock().unwrap().as_str().to_value(),
+                "fail-after" => self.inner.fail_after.load(Ordering::Relaxed).to_value(),
+                "max-inter-arrival-ms" => {
+                    self.inner.arrivals.lock().unwrap().max_gap_ms().to_value()
+                }
+                "avg-inter-arrival-ms" => {
+                    self.inner.arrivals.lock().unwrap().avg_gap_ms().to_value()
+                }
+                "jitter-ms" => self.inner.arrivals.lock().unwrap().jitter_ms().to_value(),
                 "got-eos" => (self.inner.got_eos.load(Ordering::Relaxed) != 0).to_value(),
                 "got-flush-start" => {
                     (self.inner.got_flush_start.load(Ordering::Relaxed) != 0).to_value()
@@ -132,6 +337,22 @@ pub mod counter_sink {
                 _ => false.to_value(),
             }
         }
+
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+                // Arrival times (µs since the first buffer) of the most recent buffers
+                vec![glib::subclass::Signal::builder("get-arrival-times")
+                    .action()
+                    .return_type::<gst::Array>()
+                    .class_handler(|values| {
+                        let element = values[0].get::<CounterSink>().unwrap();
+                        let arrivals = element.imp().inner.arrivals.lock().unwrap();
+                        Some(gst::Array::new(arrivals.recent.iter().copied()).to_value())
+                    })
+                    .build()]
+            });
+            SIGNALS.as_ref()
+        }
     }
 
     impl GstObjectImpl for Impl {}
@@ -174,22 +395,141 @@ pub mod counter_sink {
 }
 
 /// Encoder stub: passthrough with bitrate property and optional key unit signal
-/// Simulates an encoder for testing dynamic bitrate adjustment
+/// Simulates an encoder for testing dynamic bitrate adjustment; with
+/// `generate=true` it produces its own bitrate-sized output instead
 pub mod encoder_stub {
     use super::*;
+    use gst::subclass::prelude::ElementImplExt;
+    use std::sync::Condvar;
+    use std::thread::JoinHandle;
+    use std::time::Instant;
 
     pub struct Inner {
         bitrate_kbps: Mutex<u32>,
+        generate: Mutex<bool>,
+        fps: Mutex<u32>,
+        keyframe_interval: Mutex<u32>,
+        control: Mutex<GeneratorControl>,
+        wake: Condvar,
+        worker: Mutex<Option<JoinHandle<()>>>,
     }
 
     impl Default for Inner {
         fn default() -> Self {
             Self {
                 bitrate_kbps: Mutex::new(3000),
+                generate: Mutex::new(false),
+                fps: Mutex::new(30),
+                keyframe_interval: Mutex::new(30),
+                control: Mutex::new(GeneratorControl::default()),
+                wake: Condvar::new(),
+                worker: Mutex::new(None),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct GeneratorControl {
+        running: bool,
+        force_key: bool,
+    }
+
+    impl Inner {
+        /// Request a keyframe; wakes the generator so it is sent immediately
+        fn request_key_unit(&self) {
+            self.control.lock().unwrap().force_key = true;
+            self.wake.notify_all();
+        }
+
+        /// Bytes per output buffer for the current bitrate and frame rate
+        fn frame_size(&self) -> usize {
+            let kbps = *self.bitrate_kbps.lock().unwrap() as usize;
+            let fps = (*self.fps.lock().unwrap()).max(1) as usize;
+            (kbps * 1000 / 8 / fps).max(1)
+        }
+
+        fn start(self: &Arc<Self>, srcpad: gst::Pad) {
+            let mut worker = self.worker.lock().unwrap();
+            if worker.is_some() {
+                return;
+            }
+            self.control.lock().unwrap().running = true;
+            let inner = self.clone();
+            *worker = Some(std::thread::spawn(move || inner.generate_loop(&srcpad)));
+        }
+
+        fn stop(&self) {
+            self.control.lock().unwrap().running = false;
+            self.wake.notify_all();
+            if let Some(handle) = self.worker.lock().unwrap().take() {
+                let _ = handle.join();
+            }
+        }
+
+        /// Push frames of `frame_size()` bytes at `fps`, a keyframe every
+        /// `keyframe-interval` frames, plus one immediately on request
+        fn generate_loop(&self, srcpad: &gst::Pad) {
+            srcpad.push_event(gst::event::StreamStart::new("encoder-stub"));
+            srcpad.push_event(gst::event::Caps::new(&gst::Caps::new_empty_simple(
+                "video/x-encoder-stub",
+            )));
+            srcpad.push_event(gst::event::Segment::new(&gst::FormattedSegment::<
+                gst::ClockTime,
+            >::new()));
+
+            let start = Instant::now();
+            let mut next = start;
+            let mut since_key = u32::MAX;
+            loop {
+                let forced = {
+                    let mut control = self.control.lock().unwrap();
+                    loop {
+                        if !control.running {
+                            return;
+                        }
+                        let now = Instant::now();
+                        if control.force_key || now >= next {
+                            break;
+                        }
+                        control = self.wake.wait_timeout(control, next - now).unwrap().0;
+                    }
+                    std::mem::take(&mut control.force_key)
+                };
+
+                let interval = *self.keyframe_interval.lock().unwrap();
+                let key = forced || since_key >= interval;
+                let mut buffer = gst::Buffer::with_size(self.frame_size()).unwrap();
+                {
+                    let buffer = buffer.get_mut().unwrap();
+                    buffer.set_pts(gst::ClockTime::from_nseconds(
+                        start.elapsed().as_nanos() as u64
+                    ));
+                    if !key {
+                        buffer.set_flags(gst::BufferFlags::DELTA_UNIT);
+                    }
+                }
+                since_key = if key { 1 } else { since_key.saturating_add(1) };
+
+                match srcpad.push(buffer) {
+                    Ok(_) | Err(gst::FlowError::NotLinked) => {}
+                    Err(_) => return,
+                }
+
+                // Forced keyframes are extra output and do not shift the cadence
+                if !forced {
+                    let fps = (*self.fps.lock().unwrap()).max(1) as u64;
+                    next += Duration::from_nanos(1_000_000_000 / fps);
+                }
             }
         }
     }
 
+    fn is_force_key_unit(event: &gst::Event) -> bool {
+        event
+            .structure()
+            .is_some_and(|s| s.name() == "GstForceKeyUnit")
+    }
+
     glib::wrapper! {
         pub struct EncoderStub(ObjectSubclass<Impl>) @extends gst::Element, gst::Object;
     }
@@ -228,25 +568,50 @@ pub mod encoder_stub {
 
             let srcpad = gst::Pad::builder_from_template(&src_tmpl)
                 .name("src")
+                .event_function({
+                    let inner = self.inner.clone();
+                    move |pad, parent, event| {
+                        if is_force_key_unit(&event) {
+                            inner.request_key_unit();
+                        }
+                        gst::Pad::event_default(pad, parent, event)
+                    }
+                })
                 .build();
             let sinkpad = gst::Pad::builder_from_template(&sink_tmpl)
                 .name("sink")
-                .chain_function(|_pad, parent, buffer| {
-                    match parent.and_then(|p| p.downcast_ref::<EncoderStub>()) {
-                        Some(elem) => match elem.static_pad("src") {
-                            Some(src) => src.push(buffer),
+                .chain_function({
+                    let inner = self.inner.clone();
+                    move |_pad, parent, mut buffer| {
+                        // In passthrough mode a requested key unit marks the next buffer
+                        if std::mem::take(&mut inner.control.lock().unwrap().force_key) {
+                            buffer.make_mut().unset_flags(gst::BufferFlags::DELTA_UNIT);
+                        }
+                        match parent.and_then(|p| p.downcast_ref::<EncoderStub>()) {
+                            Some(elem) => match elem.static_pad("src") {
+                                Some(src) => src.push(buffer),
+                                None => Err(gst::FlowError::Error),
+                            },
                             None => Err(gst::FlowError::Error),
-                        },
-                        None => Err(gst::FlowError::Error),
+                        }
                     }
                 })
-                .event_function(|pad, parent, event| {
-                    if let Some(elem) = parent.and_then(|p| p.downcast_ref::<EncoderStub>()) {
-                        if let Some(src) = elem.static_pad("src") {
-                            return src.push_event(event);
+                .event_function({
+                    let inner = self.inner.clone();
+                    move |pad, parent, event| {
+                        if is_force_key_unit(&event) {
+                            inner.request_key_unit();
+                            if *inner.generate.lock().unwrap() {
+                                return true;
+                            }
+                        }
+                        if let Some(elem) = parent.and_then(|p| p.downcast_ref::<EncoderStub>()) {
+                            if let Some(src) = elem.static_pad("src") {
+                                return src.push_event(event);
+                            }
                         }
+                        gst::Pad::event_default(pad, parent, event)
                     }
-                    gst::Pad::event_default(pad, parent, event)
                 })
                 .build();
 
@@ -256,20 +621,54 @@ pub mod encoder_stub {
 
         fn properties() -> &'static [glib::ParamSpec] {
             static PROPS: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
-                vec![glib::ParamSpecUInt::builder("bitrate")
-                    .nick("Bitrate (kbps)")
-                    .default_value(3000)
-                    .minimum(100)
-                    .maximum(100000)
-                    .build()]
+                vec![
+                    glib::ParamSpecUInt::builder("bitrate")
+                        .nick("Bitrate (kbps)")
+                        .default_value(3000)
+                        .minimum(100)
+                        .maximum(100000)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("generate")
+                        .nick("Generate output")
+                        .blurb("Produce buffers sized from bitrate instead of passing input through (applied on READY->PAUSED)")
+                        .default_value(false)
+                        .build(),
+                    glib::ParamSpecUInt::builder("fps")
+                        .nick("Frames per second")
+                        .blurb("Output buffer rate when generating")
+                        .default_value(30)
+                        .minimum(1)
+                        .maximum(1000)
+                        .build(),
+                    glib::ParamSpecUInt::builder("keyframe-interval")
+                        .nick("Keyframe interval")
+                        .blurb("Generated buffers per keyframe")
+                        .default_value(30)
+                        .minimum(1)
+                        .maximum(100000)
+                        .build(),
+                ]
             });
             PROPS.as_ref()
         }
 
         fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
-            if pspec.name() == "bitrate" {
-                let v = value.get::<u32>().unwrap_or(3000);
-                *self.inner.bitrate_kbps.lock().unwrap() = v;
+            match pspec.name() {
+                "bitrate" => {
+                    let v = value.get::<u32>().unwrap_or(3000);
+                    *self.inner.bitrate_kbps.lock().unwrap() = v;
+                }
+                "generate" => {
+                    *self.inner.generate.lock().unwrap() = value.get::<bool>().unwrap_or(false);
+                }
+                "fps" => {
+                    *self.inner.fps.lock().unwrap() = value.get::<u32>().unwrap_or(30);
+                }
+                "keyframe-interval" => {
+                    *self.inner.keyframe_interval.lock().unwrap() =
+                        value.get::<u32>().unwrap_or(30);
+                }
+                _ => {}
             }
         }
 
@@ -279,13 +678,24 @@ pub mod encoder_stub {
                     let val = *self.inner.bitrate_kbps.lock().unwrap();
                     val.to_value()
                 }
+                "generate" => (*self.inner.generate.lock().unwrap()).to_value(),
+                "fps" => (*self.inner.fps.lock().unwrap()).to_value(),
+                "keyframe-interval" => (*self.inner.keyframe_interval.lock().unwrap()).to_value(),
                 _ => 0u32.to_value(),
             }
         }
 
         fn signals() -> &'static [glib::subclass::Signal] {
-            static SIGS: Lazy<Vec<glib::subclass::Signal>> =
-                Lazy::new(|| vec![glib::subclass::Signal::builder("force-key-unit").build()]);
+            static SIGS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+                vec![glib::subclass::Signal::builder("force-key-unit")
+                    .action()
+                    .class_handler(|values| {
+                        let element = values[0].get::<EncoderStub>().unwrap();
+                        element.imp().inner.request_key_unit();
+                        None
+                    })
+                    .build()]
+            });
             SIGS.as_ref()
         }
     }
@@ -326,6 +736,21 @@ pub mod encoder_stub {
             });
             TEMPLS.as_ref()
         }
+
+        fn change_state(
+            &self,
+            transition: gst::StateChange,
+        ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+            if transition == gst::StateChange::PausedToReady {
+                self.inner.stop();
+            }
+            let ret = self.parent_change_state(transition)?;
+            if transition == gst::StateChange::ReadyToPaused && *self.inner.generate.lock().unwrap()
+            {
+                self.inner.start(self.obj().static_pad("src").unwrap());
+            }
+            Ok(ret)
+        }
     }
 
     pub fn register() -> Result<(), glib::BoolError> {
@@ -356,14 +781,55 @@ pub mod riststats_mock {
         sent_original: u64,
         sent_retrans: u64,
         rtt_ms: u64,
+        lost: u64,
+        recovered: u64,
+    }
+
+    /// Which side of a RIST session the mock reports for
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub enum StatsMode {
+        #[default]
+        Sender,
+        Receiver,
+    }
+
+    impl StatsMode {
+        fn as_str(self) -> &'static str {
+            match self {
+                StatsMode::Sender => "sender",
+                StatsMode::Receiver => "receiver",
+            }
+        }
+
+        fn from_str(s: &str) -> Option<Self> {
+            match s {
+                "sender" => Some(StatsMode::Sender),
+                "receiver" => Some(StatsMode::Receiver),
+                _ => None,
+            }
+        }
+    }
+
+    /// Per-session change applied at one timeline step
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct SessionDelta {
+        pub original: u64,
+        pub retrans: u64,
+        /// New round-trip time; None keeps the current value
+        pub rtt_ms: Option<u64>,
     }
 
+    /// A timeline step: offset from `start_timeline()` and per-session deltas
+    pub type TimelineStep = (u64, Vec<SessionDelta>);
+
     #[derive(Debug)]
     struct Model {
         sessions: Vec<SessionModel>,
         custom_stats: Option<gst::Structure>,
         quality: f64,
         rtt: u32,
+        mode: StatsMode,
+        timeline: Vec<TimelineStep>,
     }
 
     impl Default for Model {
@@ -373,6 +839,8 @@ pub mod riststats_mock {
                 custom_stats: None,
                 quality: 95.0,
                 rtt: 10,
+                mode: StatsMode::Sender,
+                timeline: Vec::new(),
             }
         }
     }
@@ -384,6 +852,8 @@ pub mod riststats_mock {
     #[derive(Default)]
     pub struct Impl {
         model: Arc<Mutex<Model>>,
+        /// Bumped on start/stop so pending timeline timeouts become no-ops
+        timeline_generation: AtomicU64,
     }
 
     #[glib::object_subclass]
@@ -421,6 +891,12 @@ pub mod riststats_mock {
                         .default_value(10)
                         .flags(glib::ParamFlags::READABLE | glib::ParamFlags::WRITABLE)
                         .build(),
+                    glib::ParamSpecString::builder("mode")
+                        .nick("Stats mode")
+                        .blurb("Structure to report: sender or receiver")
+                        .default_value(Some("sender"))
+                        .flags(glib::ParamFlags::READABLE | glib::ParamFlags::WRITABLE)
+                        .build(),
                 ]
             });
             PROPS.as_ref()
@@ -446,6 +922,18 @@ pub mod riststats_mock {
                         model.rtt = rtt;
                     }
                 }
+                4 => {
+                    let requested = value.get::<Option<String>>().ok().flatten();
+                    match requested.as_deref().and_then(StatsMode::from_str) {
+                        Some(mode) => {
+                            self.model.lock().unwrap().mode = mode;
+                            self.obj().notify("stats");
+                        }
+                        None => {
+                            gst::warning!(CAT, imp = self, "Unknown stats mode {:?}", requested)
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -469,6 +957,10 @@ pub mod riststats_mock {
                     let model = self.model.lock().unwrap();
                     model.rtt.to_value()
                 }
+                4 => {
+                    let model = self.model.lock().unwrap();
+                    model.mode.as_str().to_value()
+                }
                 _ => gst::Structure::builder("rist/x-sender-stats")
                     .build()
                     .to_value(),
@@ -479,6 +971,9 @@ pub mod riststats_mock {
     impl Impl {
         fn build_stats_structure(&self) -> gst::Structure {
             let model = self.model.lock().unwrap();
+            if model.mode == StatsMode::Receiver {
+                return Self::build_receiver_structure(&model);
+            }
             let mut builder = gst::Structure::builder("rist/x-sender-stats");
 
             // Aggregated totals for compatibility with parsers expecting global fields
@@ -516,9 +1011,66 @@ pub mod riststats_mock {
                 );
             builder.build()
         }
+
+        /// Receiver view of the same sessions: every original packet sent
+        /// arrives unless reported lost and not yet recovered.
+        fn build_receiver_structure(model: &Model) -> gst::Structure {
+            let mut builder = gst::Structure::builder("rist/x-receiver-stats");
+
+            let mut total_received: u64 = 0;
+            let mut total_lost: u64 = 0;
+            let mut total_recovered: u64 = 0;
+            let mut min_rtt: f64 = f64::INFINITY;
+            for (i, sess) in model.sessions.iter().enumerate() {
+                let prefix = format!("session-{}.", i);
+                let unrecovered = sess.lost.saturating_sub(sess.recovered);
+                let received = sess.sent_original.saturating_sub(unrecovered);
+                builder = builder
+                    .field(format!("{}received-packets", prefix), received)
+                    .field(format!("{}lost-packets", prefix), sess.lost)
+                    .field(format!("{}recovered-packets", prefix), sess.recovered)
+                    .field(format!("{}round-trip-time", prefix), sess.rtt_ms as f64);
+
+                total_received = total_received.saturating_add(received);
+                total_lost = total_lost.saturating_add(sess.lost);
+                total_recovered = total_recovered.saturating_add(sess.recovered);
+                let rtt_f = sess.rtt_ms as f64;
+                if rtt_f > 0.0 && rtt_f < min_rtt {
+                    min_rtt = rtt_f;
+                }
+            }
+
+            builder = builder
+                .field("received-packets", total_received)
+                .field("lost-packets", total_lost)
+                .field("recovered-packets", total_recovered)
+                .field(
+                    "round-trip-time",
+                    if min_rtt.is_finite() { min_rtt } else { 0.0 },
+                );
+            builder.build()
+        }
     }
 
     impl RistStatsMock {
+        /// Switch between `rist/x-sender-stats` and `rist/x-receiver-stats`
+        pub fn set_mode(&self, mode: StatsMode) {
+            self.imp().model.lock().unwrap().mode = mode;
+            self.notify("stats");
+        }
+
+        /// Record packets lost on session `idx`, and how many of them RIST recovered
+        pub fn report_loss(&self, idx: usize, lost: u64, recovered: u64) {
+            let imp = self.imp();
+            let mut model = imp.model.lock().unwrap();
+            if let Some(sess) = model.sessions.get_mut(idx) {
+                sess.lost = sess.lost.saturating_add(lost);
+                sess.recovered = sess.recovered.saturating_add(recovered.min(lost));
+            }
+            drop(model);
+            self.notify("stats");
+        }
+
         /// Set the number of mock sessions
         pub fn set_sessions(&self, n: usize) {
             let imp = self.imp();
@@ -548,6 +1100,69 @@ pub mod riststats_mock {
             self.notify("stats");
         }
 
+        /// Script a stats trajectory for `start_timeline()`.
+        ///
+        /// Offsets are in milliseconds from the start and must be non-decreasing;
+        /// each step adds its deltas to the matching session like `tick()`.
+        pub fn set_timeline(&self, steps: Vec<TimelineStep>) {
+            self.stop_timeline();
+            self.imp().model.lock().unwrap().timeline = steps;
+        }
+
+        /// Play the timeline on the default main context, notifying "stats" at each step
+        pub fn start_timeline(&self) {
+            let generation = self
+                .imp()
+                .timeline_generation
+                .fetch_add(1, Ordering::SeqCst)
+                + 1;
+            self.schedule_timeline_step(generation, 0, 0);
+        }
+
+        /// Stop a running timeline; steps already applied are kept
+        pub fn stop_timeline(&self) {
+            self.imp()
+                .timeline_generation
+                .fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn schedule_timeline_step(&self, generation: u64, idx: usize, elapsed_ms: u64) {
+            let offset_ms = match self.imp().model.lock().unwrap().timeline.get(idx) {
+                Some((offset_ms, _)) => *offset_ms,
+                None => return,
+            };
+            let weak = self.downgrade();
+            let wait = Duration::from_millis(offset_ms.saturating_sub(elapsed_ms));
+            gst::glib::timeout_add(wait, move || {
+                let Some(mock) = weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                if mock.imp().timeline_generation.load(Ordering::SeqCst) != generation {
+                    return glib::ControlFlow::Break;
+                }
+                mock.apply_timeline_step(idx);
+                mock.schedule_timeline_step(generation, idx + 1, offset_ms);
+                glib::ControlFlow::Break
+            });
+        }
+
+        fn apply_timeline_step(&self, idx: usize) {
+            let mut model = self.imp().model.lock().unwrap();
+            let Some((_, deltas)) = model.timeline.get(idx).cloned() else {
+                return;
+            };
+            for (sess, delta) in model.sessions.iter_mut().zip(deltas.iter()) {
+                sess.sent_original = sess.sent_original.saturating_add(delta.original);
+                sess.sent_retrans = sess.sent_retrans.saturating_add(delta.retrans);
+                if let Some(rtt_ms) = delta.rtt_ms {
+                    sess.rtt_ms = rtt_ms;
+                }
+            }
+            drop(model);
+            gst::debug!(CAT, obj = self, "Applied timeline step {}", idx);
+            self.notify("stats");
+        }
+
         /// Simulate network degradation
         pub fn degrade(&self, idx: usize, extra_retrans: u64, new_rtt: u64) {
             let imp = self.imp();
@@ -682,3 +1297,668 @@ pub mod riststats_mock {
         )
     }
 }
+
+/// RTP sequence source: emits small application/x-rtp packets with
+/// incrementing sequence numbers at a fixed rate, for loss/reorder checks
+pub mod rtp_seq_src {
+    use super::*;
+    use gst::subclass::prelude::ElementImplExt;
+    use std::sync::Condvar;
+    use std::thread::JoinHandle;
+    use std::time::Instant;
+
+    const SSRC: u32 = 0x5253_4551;
+    const PAYLOAD_TYPE: u8 = 96;
+
+    pub struct Inner {
+        rate: Mutex<u32>,
+        payload_size: Mutex<u32>,
+        num_packets: Mutex<u64>,
+        sent: AtomicU64,
+        running: Mutex<bool>,
+        wake: Condvar,
+        worker: Mutex<Option<JoinHandle<()>>>,
+    }
+
+    impl Default for Inner {
+        fn default() -> Self {
+            Self {
+                rate: Mutex::new(100),
+                payload_size: Mutex::new(64),
+                num_packets: Mutex::new(0),
+                sent: AtomicU64::new(0),
+                running: Mutex::new(false),
+                wake: Condvar::new(),
+                worker: Mutex::new(None),
+            }
+        }
+    }
+
+    /// Minimal RTP packet: 12-byte header followed by a zeroed payload
+    fn rtp_packet(seq: u16, timestamp: u32, payload_size: usize) -> gst::Buffer {
+        let mut data = vec![0u8; 12 + payload_size];
+        data[0] = 0x80;
+        data[1] = PAYLOAD_TYPE;
+        data[2..4].copy_from_slice(&seq.to_be_bytes());
+        data[4..8].copy_from_slice(&timestamp.to_be_bytes());
+        data[8..12].copy_from_slice(&SSRC.to_be_bytes());
+        gst::Buffer::from_mut_slice(data)
+    }
+
+    impl Inner {
+        fn start(self: &Arc<Self>, srcpad: gst::Pad) {
+            let mut worker = self.worker.lock().unwrap();
+            if worker.is_some() {
+                return;
+            }
+            // seq restarts at 0 with each run, so the count does too
+            self.sent.store(0, Ordering::Relaxed);
+            *self.running.lock().unwrap() = true;
+            let inner = self.clone();
+            *worker = Some(std::thread::spawn(move || inner.send_loop(&srcpad)));
+        }
+
+        fn stop(&self) {
+            *self.running.lock().unwrap() = false;
+            self.wake.notify_all();
+            if let Some(handle) = self.worker.lock().unwrap().take() {
+                let _ = handle.join();
+            }
+        }
+
+        fn send_loop(&self, srcpad: &gst::Pad) {
+            srcpad.push_event(gst::event::StreamStart::new("rtp-seq-src"));
+            srcpad.push_event(gst::event::Caps::new(
+                &gst::Caps::builder("application/x-rtp")
+                    .field("media", "application")
+                    .field("clock-rate", 90000i32)
+                    .field("payload", PAYLOAD_TYPE as i32)
+                    .build(),
+            ));
+            srcpad.push_event(gst::event::Segment::new(&gst::FormattedSegment::<
+                gst::ClockTime,
+            >::new()));
+
+            let start = Instant::now();
+            let mut next = start;
+            let limit = *self.num_packets.lock().unwrap();
+            let mut seq: u16 = 0;
+            loop {
+                {
+                    let mut running = self.running.lock().unwrap();
+                    loop {
+                        if !*running {
+                            return;
+                        }
+                        let now = Instant::now();
+                        if now >= next {
+                            break;
+                        }
+                        running = self.wake.wait_timeout(running, next - now).unwrap().0;
+                    }
+                }
+
+                let elapsed = start.elapsed();
+                let timestamp = (elapsed.as_micros() as u64 * 90 / 1000) as u32;
+                let payload_size = *self.payload_size.lock().unwrap() as usize;
+                let mut buffer = rtp_packet(seq, timestamp, payload_size);
+                buffer
+                    .get_mut()
+                    .unwrap()
+                    .set_pts(gst::ClockTime::from_nseconds(elapsed.as_nanos() as u64));
+
+                match srcpad.push(buffer) {
+                    Ok(_) | Err(gst::FlowError::NotLinked) => {}
+                    Err(_) => return,
+                }
+                seq = seq.wrapping_add(1);
+                let sent = self.sent.fetch_add(1, Ordering::Relaxed) + 1;
+                if limit > 0 && sent >= limit {
+                    srcpad.push_event(gst::event::Eos::new());
+                    return;
+                }
+
+                let rate = (*self.rate.lock().unwrap()).max(1) as u64;
+                next += Duration::from_nanos(1_000_000_000 / rate);
+            }
+        }
+    }
+
+    glib::wrapper! {
+        pub struct RtpSeqSrc(ObjectSubclass<Impl>) @extends gst::Element, gst::Object;
+    }
+
+    #[derive(Default)]
+    pub struct Impl {
+        inner: Arc<Inner>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Impl {
+        const NAME: &'static str = "rtp_seq_src";
+        type Type = RtpSeqSrc;
+        type ParentType = gst::Element;
+    }
+
+    impl ObjectImpl for Impl {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            let src_tmpl = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &gst::Caps::new_empty_simple("application/x-rtp"),
+            )
+            .unwrap();
+            let srcpad = gst::Pad::builder_from_template(&src_tmpl)
+                .name("src")
+                .build();
+            obj.add_pad(&srcpad).unwrap();
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPS: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![
+                    glib::ParamSpecUInt::builder("rate")
+                        .nick("Packet rate")
+                        .blurb("Packets per second")
+                        .default_value(100)
+                        .minimum(1)
+                        .maximum(100000)
+                        .build(),
+                    glib::ParamSpecUInt::builder("payload-size")
+                        .nick("Payload size")
+                        .blurb("RTP payload bytes per packet")
+                        .default_value(64)
+                        .maximum(1400)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("num-packets")
+                        .nick("Number of packets")
+                        .blurb("Packets to send before EOS (0 = unlimited)")
+                        .default_value(0)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("sent")
+                        .nick("Sent")
+                        .blurb("Packets pushed so far")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                ]
+            });
+            PROPS.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            match pspec.name() {
+                "rate" => *self.inner.rate.lock().unwrap() = value.get::<u32>().unwrap_or(100),
+                "payload-size" => {
+                    *self.inner.payload_size.lock().unwrap() = value.get::<u32>().unwrap_or(64)
+                }
+                "num-packets" => {
+                    *self.inner.num_packets.lock().unwrap() = value.get::<u64>().unwrap_or(0)
+                }
+                _ => {}
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "rate" => (*self.inner.rate.lock().unwrap()).to_value(),
+                "payload-size" => (*self.inner.payload_size.lock().unwrap()).to_value(),
+                "num-packets" => (*self.inner.num_packets.lock().unwrap()).to_value(),
+                "sent" => self.inner.sent.load(Ordering::Relaxed).to_value(),
+                _ => 0u32.to_value(),
+            }
+        }
+    }
+
+    impl GstObjectImpl for Impl {}
+
+    impl ElementImpl for Impl {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static META: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+                gst::subclass::ElementMetadata::new(
+                    "RTP Sequence Source",
+                    "Source/Testing",
+                    "Emits RTP packets with incrementing sequence numbers for testing",
+                    "RIST Test Harness",
+                )
+            });
+            Some(&*META)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static TEMPLS: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+                vec![gst::PadTemplate::new(
+                    "src",
+                    gst::PadDirection::Src,
+                    gst::PadPresence::Always,
+                    &gst::Caps::new_empty_simple("application/x-rtp"),
+                )
+                .unwrap()]
+            });
+            TEMPLS.as_ref()
+        }
+
+        fn change_state(
+            &self,
+            transition: gst::StateChange,
+        ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+            if transition == gst::StateChange::PausedToReady {
+                self.inner.stop();
+            }
+            let ret = self.parent_change_state(transition)?;
+            if transition == gst::StateChange::ReadyToPaused {
+                self.inner.start(self.obj().static_pad("src").unwrap());
+            }
+            Ok(ret)
+        }
+    }
+
+    pub fn register() -> Result<(), glib::BoolError> {
+        gst::Element::register(
+            None,
+            "rtp_seq_src",
+            gst::Rank::NONE,
+            RtpSeqSrc::static_type(),
+        )
+    }
+}
+
+/// RTP sequence check: sink counting received, missing, duplicate and
+/// out-of-order packets from their RTP sequence numbers
+pub mod rtp_seq_check {
+    use super::*;
+    use gst::subclass::prelude::ElementImplExt;
+    use std::collections::HashSet;
+
+    /// Sequence numbers remembered for duplicate detection
+    const SEEN_WINDOW: u64 = 4096;
+
+    /// Loss and ordering accounting over extended (unwrapped) sequence numbers
+    #[derive(Default)]
+    struct SeqTracker {
+        first: u64,
+        highest: Option<u64>,
+        seen: HashSet<u64>,
+        received: u64,
+        missing: u64,
+        duplicates: u64,
+        out_of_order: u64,
+    }
+
+    impl SeqTracker {
+        fn record(&mut self, seq: u16) {
+            let Some(highest) = self.highest else {
+                self.first = seq as u64 + (1 << 16);
+                self.highest = Some(self.first);
+                self.seen.insert(self.first);
+                self.received = 1;
+                return;
+            };
+
+            let diff = seq.wrapping_sub(highest as u16) as i16 as i64;
+            let ext = (highest as i64 + diff) as u64;
+            if !self.seen.insert(ext) {
+                self.duplicates += 1;
+                return;
+            }
+            self.received += 1;
+            if diff > 0 {
+                // Anything skipped is missing until it turns up late
+                self.missing += diff as u64 - 1;
+                self.highest = Some(ext);
+                if self.seen.len() as u64 > 2 * SEEN_WINDOW {
+                    self.seen.retain(|&s| s + SEEN_WINDOW > ext);
+                }
+            } else {
+                self.out_of_order += 1;
+                if ext > self.first {
+                    self.missing = self.missing.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    glib::wrapper! {
+        pub struct RtpSeqCheck(ObjectSubclass<Impl>) @extends gst::Element, gst::Object;
+    }
+
+    #[derive(Default)]
+    pub struct Impl {
+        tracker: Arc<Mutex<SeqTracker>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Impl {
+        const NAME: &'static str = "rtp_seq_check";
+        type Type = RtpSeqCheck;
+        type ParentType = gst::Element;
+    }
+
+    impl ObjectImpl for Impl {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            let sink_tmpl = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &gst::Caps::new_any(),
+            )
+            .unwrap();
+
+            let tracker = self.tracker.clone();
+            let sinkpad = gst::Pad::builder_from_template(&sink_tmpl)
+                .name("sink")
+                .chain_function(move |_pad, _parent, buf| {
+                    let map = buf.map_readable().map_err(|_| gst::FlowError::Error)?;
+                    if map.len() >= 12 && map[0] >> 6 == 2 {
+                        let seq = u16::from_be_bytes([map[2], map[3]]);
+                        tracker.lock().unwrap().record(seq);
+                    }
+                    Ok(gst::FlowSuccess::Ok)
+                })
+                .build();
+
+            obj.add_pad(&sinkpad).unwrap();
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPS: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![
+                    glib::ParamSpecUInt64::builder("received")
+                        .blurb("Distinct packets received")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("missing")
+                        .blurb("Sequence numbers skipped and not received late")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("duplicates")
+                        .blurb("Packets whose sequence number was already received")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecUInt64::builder("out-of-order")
+                        .blurb("Packets arriving after a higher sequence number")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                ]
+            });
+            PROPS.as_ref()
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            let tracker = self.tracker.lock().unwrap();
+            match pspec.name() {
+                "received" => tracker.received.to_value(),
+                "missing" => tracker.missing.to_value(),
+                "duplicates" => tracker.duplicates.to_value(),
+                "out-of-order" => tracker.out_of_order.to_value(),
+                _ => 0u64.to_value(),
+            }
+        }
+    }
+
+    impl GstObjectImpl for Impl {}
+
+    impl ElementImpl for Impl {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static META: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+                gst::subclass::ElementMetadata::new(
+                    "RTP Sequence Check",
+                    "Sink/Testing",
+                    "Counts missing, duplicate and out-of-order RTP packets for testing",
+                    "RIST Test Harness",
+                )
+            });
+            Some(&*META)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static TEMPLS: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+                vec![gst::PadTemplate::new(
+                    "sink",
+                    gst::PadDirection::Sink,
+                    gst::PadPresence::Always,
+                    &gst::Caps::new_any(),
+                )
+                .unwrap()]
+            });
+            TEMPLS.as_ref()
+        }
+
+        fn change_state(
+            &self,
+            transition: gst::StateChange,
+        ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+            // A restarted source begins again at its first sequence number
+            if transition == gst::StateChange::ReadyToPaused {
+                *self.tracker.lock().unwrap() = SeqTracker::default();
+            }
+            self.parent_change_state(transition)
+        }
+    }
+
+    pub fn register() -> Result<(), glib::BoolError> {
+        gst::Element::register(
+            None,
+            "rtp_seq_check",
+            gst::Rank::NONE,
+            RtpSeqCheck::static_type(),
+        )
+    }
+}
+
+/// RIST sink mock: request sink pads standing in for ristsink sessions.
+/// Counts buffers per pad and reports them through a ristsink-shaped
+/// `stats` property, with injectable retransmission rate and RTT.
+pub mod ristsink_mock {
+    use super::*;
+
+    static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+        gst::DebugCategory::new(
+            "ristsink-mock",
+            gst::DebugColorFlags::empty(),
+            Some("Mock RIST sink"),
+        )
+    });
+
+    struct Session {
+        pad: gst::Pad,
+        index: u32,
+        count: Arc<AtomicU64>,
+        /// Fraction of originals reported as retransmitted
+        rtx_rate: f64,
+        rtt_ms: u64,
+    }
+
+    #[derive(Default)]
+    struct State {
+        sessions: Vec<Session>,
+        next_index: u32,
+    }
+
+    glib::wrapper! {
+        pub struct RistSinkMock(ObjectSubclass<Impl>) @extends gst::Element, gst::Object;
+    }
+
+    #[derive(Default)]
+    pub struct Impl {
+        state: Mutex<State>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Impl {
+        const NAME: &'static str = "ristsink_mock";
+        type Type = RistSinkMock;
+        type ParentType = gst::Element;
+    }
+
+    impl ObjectImpl for Impl {
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPS: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![glib::ParamSpecBoxed::builder::<gst::Structure>("stats")
+                    .nick("Stats structure")
+                    .blurb("Sender statistics with a session-stats array, as ristsink reports")
+                    .flags(glib::ParamFlags::READABLE)
+                    .build()]
+            });
+            PROPS.as_ref()
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "stats" => self.build_stats_structure().to_value(),
+                _ => gst::Structure::builder("rist/x-sender-stats")
+                    .build()
+                    .to_value(),
+            }
+        }
+    }
+
+    impl Impl {
+        fn build_stats_structure(&self) -> gst::Structure {
+            let state = self.state.lock().unwrap();
+            let mut total_original: u64 = 0;
+            let mut total_retrans: u64 = 0;
+            let sessions: Vec<gst::Structure> = state
+                .sessions
+                .iter()
+                .map(|sess| {
+                    let original = sess.count.load(Ordering::Relaxed);
+                    let retrans = (original as f64 * sess.rtx_rate).round() as u64;
+                    total_original = total_original.saturating_add(original);
+                    total_retrans = total_retrans.saturating_add(retrans);
+                    gst::Structure::builder("session-stats")
+                        .field("session-id", sess.index as i32)
+                        .field("sent-original-packets", original)
+                        .field("sent-retransmitted-packets", retrans)
+                        // ristsink reports RTT in nanoseconds
+                        .field("round-trip-time", sess.rtt_ms * 1_000_000)
+                        .build()
+                })
+                .collect();
+
+            gst::Structure::builder("rist/x-sender-stats")
+                .field("sent-original-packets", total_original)
+                .field("sent-retransmitted-packets", total_retrans)
+                .field("session-stats", glib::ValueArray::new(sessions))
+                .build()
+        }
+    }
+
+    impl RistSinkMock {
+        /// Buffers received on the pad for session `idx` (request order)
+        pub fn session_count(&self, idx: usize) -> u64 {
+            let state = self.imp().state.lock().unwrap();
+            state
+                .sessions
+                .get(idx)
+                .map_or(0, |s| s.count.load(Ordering::Relaxed))
+        }
+
+        /// Set the retransmission fraction and RTT reported for session `idx`
+        pub fn set_session_quality(&self, idx: usize, rtx_rate: f64, rtt_ms: u64) {
+            let mut state = self.imp().state.lock().unwrap();
+            if let Some(sess) = state.sessions.get_mut(idx) {
+                sess.rtx_rate = rtx_rate.clamp(0.0, 1.0);
+                sess.rtt_ms = rtt_ms;
+            }
+            drop(state);
+            self.notify("stats");
+        }
+    }
+
+    impl GstObjectImpl for Impl {}
+
+    impl ElementImpl for Impl {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static META: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+                gst::subclass::ElementMetadata::new(
+                    "RIST Sink Mock",
+                    "Sink/Testing",
+                    "Request-pad sink reporting ristsink-style session statistics for testing",
+                    "RIST Test Harness",
+                )
+            });
+            Some(&*META)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static TEMPLS: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+                vec![gst::PadTemplate::new(
+                    "sink_%u",
+                    gst::PadDirection::Sink,
+                    gst::PadPresence::Request,
+                    &gst::Caps::new_any(),
+                )
+                .unwrap()]
+            });
+            TEMPLS.as_ref()
+        }
+
+        fn request_new_pad(
+            &self,
+            templ: &gst::PadTemplate,
+            name: Option<&str>,
+            _caps: Option<&gst::Caps>,
+        ) -> Option<gst::Pad> {
+            let mut state = self.state.lock().unwrap();
+            let index = name
+                .and_then(|n| n.strip_prefix("sink_"))
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(state.next_index);
+            if state.sessions.iter().any(|s| s.index == index) {
+                return None;
+            }
+            state.next_index = state.next_index.max(index + 1);
+
+            let count = Arc::new(AtomicU64::new(0));
+            let pad = gst::Pad::builder_from_template(templ)
+                .name(format!("sink_{}", index))
+                .chain_function({
+                    let count = count.clone();
+                    move |_pad, _parent, _buf| {
+                        count.fetch_add(1, Ordering::Relaxed);
+                        Ok(gst::FlowSuccess::Ok)
+                    }
+                })
+                .build();
+            state.sessions.push(Session {
+                pad: pad.clone(),
+                index,
+                count,
+                rtx_rate: 0.0,
+                rtt_ms: 20,
+            });
+            drop(state);
+
+            pad.set_active(true).ok()?;
+            self.obj().add_pad(&pad).ok()?;
+            gst::debug!(CAT, imp = self, "Added session pad {}", pad.name());
+            Some(pad)
+        }
+
+        fn release_pad(&self, pad: &gst::Pad) {
+            self.state
+                .lock()
+                .unwrap()
+                .sessions
+                .retain(|s| &s.pad != pad);
+            let _ = pad.set_active(false);
+            let _ = self.obj().remove_pad(pad);
+        }
+    }
+
+    pub fn register() -> Result<(), glib::BoolError> {
+        gst::Element::register(
+            None,
+            "ristsink_mock",
+            gst::Rank::NONE,
+            RistSinkMock::static_type(),
+        )
+    }
+}
@@ -9,13 +9,14 @@
 //! ```
 
 #[cfg(feature = "test-plugin")]
-use crate::test_harness::RistStatsMock;
+use crate::test_harness::{RistSinkMock, RistStatsMock};
 use gst::prelude::*;
 use gstreamer as gst;
 
 /// Network simulation integration (requires the `network-sim` feature)
 #[cfg(feature = "network-sim")]
 pub mod network_sim {
+    use network_sim::loopback::{self, ImpairedProxy};
     use network_sim::qdisc::QdiscManager;
     use network_sim::{apply_network_params, NetworkParams};
 
@@ -46,6 +47,33 @@ pub mod network_sim {
     pub async fn apply_good_conditions(interface: &str) -> Result<(), Box<dyn std::error::Error>> {
         apply_test_network_params(interface, NetworkParams::good()).await
     }
+
+    /// Localhost impairment through the userspace loopback proxy
+    ///
+    /// Nothing is installed on `lo`, so other traffic on the host is left
+    /// alone and nothing needs tearing down if a test panics.
+    pub struct LocalhostImpairment {
+        /// Port senders should target on 127.0.0.1
+        pub port: u16,
+        _proxy: ImpairedProxy,
+    }
+
+    impl LocalhostImpairment {
+        /// Remove the impairment
+        pub fn clear(self) {}
+    }
+
+    /// Impair localhost traffic towards `upstream` in both directions
+    pub async fn impair_localhost(
+        params: NetworkParams,
+        upstream: std::net::SocketAddr,
+    ) -> Result<LocalhostImpairment, Box<dyn std::error::Error>> {
+        let proxy = loopback::impair(&params, upstream).await?;
+        Ok(LocalhostImpairment {
+            port: proxy.port,
+            _proxy: proxy,
+        })
+    }
 }
 
 /// Initialize GStreamer and register all RIST elements for testing
@@ -79,6 +107,16 @@ pub fn create_mock_stats(num_sessions: usize) -> RistStatsMock {
     mock
 }
 
+/// Create a mock RIST sink whose request pads act as bonded sessions
+#[cfg(feature = "test-plugin")]
+pub fn create_ristsink_mock() -> RistSinkMock {
+    gst::ElementFactory::make("ristsink_mock")
+        .build()
+        .expect("Failed to create ristsink_mock")
+        .downcast::<RistSinkMock>()
+        .unwrap()
+}
+
 /// Create a RIST dispatcher element with specified weights
 pub fn create_dispatcher(weights: Option<&[f64]>) -> gst::Element {
     let dispatcher = gst::ElementFactory::make("ristdispatcher")
@@ -121,6 +159,24 @@ pub fn create_counter_sink() -> gst::Element {
         .expect("Failed to create counter_sink")
 }
 
+/// Create an RTP sequence source sending `rate` packets per second
+#[cfg(feature = "test-plugin")]
+pub fn create_rtp_seq_src(rate: u32, num_packets: u64) -> gst::Element {
+    gst::ElementFactory::make("rtp_seq_src")
+        .property("rate", rate)
+        .property("num-packets", num_packets)
+        .build()
+        .expect("Failed to create rtp_seq_src")
+}
+
+/// Create an RTP sequence checker sink
+#[cfg(feature = "test-plugin")]
+pub fn create_rtp_seq_check() -> gst::Element {
+    gst::ElementFactory::make("rtp_seq_check")
+        .build()
+        .expect("Failed to create rtp_seq_check")
+}
+
 /// Create an encoder stub element for testing bitrate control
 #[cfg(feature = "test-plugin")]
 pub fn create_encoder_stub(initial_bitrate: Option<u32>) -> gst::Element {
@@ -300,6 +356,259 @@ pub fn create_rist_source(address: &str) -> gst::Element {
         .expect("Failed to create ristsrc")
 }
 
+/// Builders for the pipeline topologies most integration tests share
+#[cfg(feature = "test-plugin")]
+pub mod pipelines {
+    use super::*;
+    use crate::test_harness::CounterSink;
+    use crate::{Dispatcher, DynBitrate};
+
+    /// `create_test_source() → dispatcher → n × counter_sink`, not yet started
+    pub fn dispatcher_with_counters(
+        n: usize,
+        weights: Option<&[f64]>,
+    ) -> (gst::Pipeline, Dispatcher, Vec<CounterSink>) {
+        init_for_tests();
+
+        let pipeline = gst::Pipeline::new();
+        let source = create_test_source();
+        let dispatcher = create_dispatcher_for_testing(weights)
+            .downcast::<Dispatcher>()
+            .expect("ristdispatcher type");
+        pipeline
+            .add_many([&source, dispatcher.upcast_ref()])
+            .expect("add source and dispatcher");
+        source.link(&dispatcher).expect("link source to dispatcher");
+
+        let counters = (0..n)
+            .map(|_| attach_counter(&pipeline, &dispatcher.request_pad_simple("src_%u").unwrap()))
+            .collect();
+
+        (pipeline, dispatcher, counters)
+    }
+
+    /// `encoder → dynbitrate → sink`, with dynbitrate reading `rist_mock`.
+    ///
+    /// With a dispatcher, dynbitrate feeds it and is told to coordinate with
+    /// it; each unlinked dispatcher src pad (or one new pad if it has none)
+    /// ends in a counter_sink. Without one, dynbitrate feeds a fakesink.
+    pub fn dynbitrate_chain(
+        encoder: &gst::Element,
+        dispatcher: Option<&Dispatcher>,
+        rist_mock: &RistStatsMock,
+    ) -> (gst::Pipeline, DynBitrate) {
+        init_for_tests();
+
+        let pipeline = gst::Pipeline::new();
+        let dynbitrate = create_dynbitrate()
+            .downcast::<DynBitrate>()
+            .expect("dynbitrate type");
+        dynbitrate.set_property("encoder", encoder);
+        dynbitrate.set_property("rist", rist_mock);
+        pipeline
+            .add_many([encoder, dynbitrate.upcast_ref()])
+            .expect("add encoder and dynbitrate");
+        encoder
+            .link(&dynbitrate)
+            .expect("link encoder to dynbitrate");
+
+        match dispatcher {
+            Some(dispatcher) => {
+                dynbitrate.set_property("dispatcher", dispatcher);
+                pipeline.add(dispatcher).expect("add dispatcher");
+                dynbitrate
+                    .link(dispatcher)
+                    .expect("link dynbitrate to dispatcher");
+                let mut outputs: Vec<gst::Pad> = dispatcher
+                    .src_pads()
+                    .into_iter()
+                    .filter(|p| !p.is_linked())
+                    .collect();
+                if outputs.is_empty() && dispatcher.src_pads().is_empty() {
+                    outputs.push(dispatcher.request_pad_simple("src_%u").unwrap());
+                }
+                for pad in outputs {
+                    attach_counter(&pipeline, &pad);
+                }
+            }
+            None => {
+                let sink = create_fake_sink();
+                pipeline.add(&sink).expect("add fakesink");
+                dynbitrate.link(&sink).expect("link dynbitrate to fakesink");
+            }
+        }
+
+        (pipeline, dynbitrate)
+    }
+
+    fn attach_counter(pipeline: &gst::Pipeline, srcpad: &gst::Pad) -> CounterSink {
+        let counter = create_counter_sink()
+            .downcast::<CounterSink>()
+            .expect("counter_sink type");
+        pipeline.add(&counter).expect("add counter_sink");
+        srcpad
+            .link(&counter.static_pad("sink").unwrap())
+            .expect("link to counter_sink");
+        counter
+    }
+}
+
+/// Assertions on how buffers were split across dispatcher outputs
+pub mod weights {
+    use super::*;
+    #[cfg(feature = "test-plugin")]
+    use crate::test_harness::CounterSink;
+
+    /// Observed share of each output, or all zeros if nothing was counted
+    pub fn shares(counts: &[u64]) -> Vec<f64> {
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return vec![0.0; counts.len()];
+        }
+        counts.iter().map(|&c| c as f64 / total as f64).collect()
+    }
+
+    /// Pearson chi-square statistic of `counts` against `expected_ratios`
+    ///
+    /// Ratios are normalised, so `[4.0, 1.0]` and `[0.8, 0.2]` are equivalent.
+    /// Outputs with an expected ratio of zero are skipped.
+    pub fn chi_square(counts: &[u64], expected_ratios: &[f64]) -> f64 {
+        let total: u64 = counts.iter().sum();
+        let ratio_sum: f64 = expected_ratios.iter().sum();
+        counts
+            .iter()
+            .zip(expected_ratios)
+            .filter(|(_, &r)| r > 0.0)
+            .map(|(&c, &r)| {
+                let expected = total as f64 * r / ratio_sum;
+                (c as f64 - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    /// Check that each output's share is within `tolerance` of its expected ratio
+    ///
+    /// `tolerance` is an absolute share difference: 0.1 accepts 0.7–0.9 for
+    /// an expected 0.8. The error lists observed and expected shares for
+    /// every output.
+    pub fn check_split(
+        counts: &[u64],
+        expected_ratios: &[f64],
+        tolerance: f64,
+    ) -> Result<(), String> {
+        if counts.len() != expected_ratios.len() {
+            return Err(format!(
+                "{} counts for {} expected ratios",
+                counts.len(),
+                expected_ratios.len()
+            ));
+        }
+        let total: u64 = counts.iter().sum();
+        let ratio_sum: f64 = expected_ratios.iter().sum();
+        if total == 0 || ratio_sum <= 0.0 {
+            return Err(format!(
+                "cannot check split: {} buffers, ratio sum {}",
+                total, ratio_sum
+            ));
+        }
+
+        let observed = shares(counts);
+        let failed = observed
+            .iter()
+            .zip(expected_ratios)
+            .any(|(o, r)| (o - r / ratio_sum).abs() > tolerance);
+        if !failed {
+            return Ok(());
+        }
+
+        let mut msg = format!(
+            "buffer split outside ±{:.3} over {} buffers (chi² = {:.2}):",
+            tolerance,
+            total,
+            chi_square(counts, expected_ratios)
+        );
+        for (i, ((count, o), r)) in counts
+            .iter()
+            .zip(&observed)
+            .zip(expected_ratios)
+            .enumerate()
+        {
+            let expected = r / ratio_sum;
+            let mark = if (o - expected).abs() > tolerance {
+                " <--"
+            } else {
+                ""
+            };
+            msg.push_str(&format!(
+                "\n  output {}: {} buffers, observed {:.3}, expected {:.3}{}",
+                i, count, o, expected, mark
+            ));
+        }
+        Err(msg)
+    }
+
+    /// Panic with an observed-vs-expected table unless the split matches
+    #[track_caller]
+    pub fn assert_split(counts: &[u64], expected_ratios: &[f64], tolerance: f64) {
+        if let Err(msg) = check_split(counts, expected_ratios, tolerance) {
+            panic!("{}", msg);
+        }
+    }
+
+    /// Current buffer count of each counter_sink
+    #[cfg(feature = "test-plugin")]
+    pub fn collect_counts(counters: &[CounterSink]) -> Vec<u64> {
+        counters
+            .iter()
+            .map(|c| c.property::<u64>("count"))
+            .collect()
+    }
+
+    /// Run `pipeline` until `counters` have received `buffers` in total,
+    /// then stop it and return the per-counter counts.
+    ///
+    /// Stops early on EOS; fails on a pipeline error or after `timeout`.
+    #[cfg(feature = "test-plugin")]
+    pub fn run_for_buffers(
+        pipeline: &gst::Pipeline,
+        counters: &[CounterSink],
+        buffers: u64,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+        let bus = pipeline.bus().unwrap();
+        pipeline.set_state(gst::State::Playing)?;
+        let deadline = std::time::Instant::now() + timeout;
+
+        let result = loop {
+            if collect_counts(counters).iter().sum::<u64>() >= buffers {
+                break Ok(());
+            }
+            if let Some(msg) = bus.timed_pop_filtered(
+                gst::ClockTime::from_mseconds(10),
+                &[gst::MessageType::Eos, gst::MessageType::Error],
+            ) {
+                match msg.view() {
+                    gst::MessageView::Error(err) => {
+                        break Err(format!("Pipeline error: {}", err.error()).into())
+                    }
+                    _ => break Ok(()),
+                }
+            }
+            if std::time::Instant::now() >= deadline {
+                break Err(format!(
+                    "Timeout waiting for {} buffers, got {:?}",
+                    buffers,
+                    collect_counts(counters)
+                )
+                .into());
+            }
+        };
+
+        pipeline.set_state(gst::State::Null)?;
+        result.map(|_| collect_counts(counters))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,6 +633,90 @@ mod tests {
         assert!(gst::ElementFactory::find("riststats_mock").is_some());
     }
 
+    #[test]
+    #[cfg(feature = "test-plugin")]
+    fn test_dispatcher_with_counters_pipeline() {
+        let (pipeline, dispatcher, counters) =
+            pipelines::dispatcher_with_counters(3, Some(&[0.5, 0.3, 0.2]));
+        assert_eq!(counters.len(), 3);
+        assert_eq!(dispatcher.src_pads().len(), 3);
+        assert!(dispatcher.static_pad("sink").unwrap().is_linked());
+        for (pad, counter) in dispatcher.src_pads().iter().zip(&counters) {
+            assert_eq!(pad.peer(), counter.static_pad("sink"));
+        }
+
+        pipeline.set_state(gst::State::Playing).unwrap();
+        let (result, state, _) = pipeline.state(gst::ClockTime::from_seconds(5));
+        assert!(result.is_ok());
+        assert_eq!(state, gst::State::Playing);
+        pipeline.set_state(gst::State::Null).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "test-plugin")]
+    fn test_dynbitrate_chain_pipeline() {
+        init_for_tests();
+        let mock = create_mock_stats(1);
+
+        let encoder = create_encoder_stub(Some(2000));
+        encoder.set_property("generate", true);
+        let (pipeline, dynbitrate) = pipelines::dynbitrate_chain(&encoder, None, &mock);
+        assert_eq!(
+            dynbitrate.property::<Option<gst::Element>>("encoder"),
+            Some(encoder.clone())
+        );
+        assert!(dynbitrate.static_pad("src").unwrap().is_linked());
+        pipeline.set_state(gst::State::Playing).unwrap();
+        let (result, state, _) = pipeline.state(gst::ClockTime::from_seconds(5));
+        assert!(result.is_ok() && state == gst::State::Playing);
+        pipeline.set_state(gst::State::Null).unwrap();
+
+        let encoder = create_encoder_stub(Some(2000));
+        encoder.set_property("generate", true);
+        let dispatcher = create_dispatcher_for_testing(Some(&[1.0]))
+            .downcast::<crate::Dispatcher>()
+            .unwrap();
+        let (pipeline, dynbitrate) =
+            pipelines::dynbitrate_chain(&encoder, Some(&dispatcher), &mock);
+        assert!(dynbitrate.static_pad("src").unwrap().is_linked());
+        assert_eq!(dispatcher.src_pads().len(), 1);
+        assert!(dispatcher.src_pads()[0].is_linked());
+        pipeline.set_state(gst::State::Playing).unwrap();
+        let (result, state, _) = pipeline.state(gst::ClockTime::from_seconds(5));
+        assert!(result.is_ok() && state == gst::State::Playing);
+        pipeline.set_state(gst::State::Null).unwrap();
+    }
+
+    #[test]
+    fn test_split_shares_and_chi_square() {
+        assert_eq!(weights::shares(&[80, 20]), vec![0.8, 0.2]);
+        assert_eq!(weights::shares(&[0, 0]), vec![0.0, 0.0]);
+
+        // Exact match has no deviation; ratios are normalised
+        assert_eq!(weights::chi_square(&[80, 20], &[4.0, 1.0]), 0.0);
+        // (70-80)²/80 + (30-20)²/20 = 1.25 + 5
+        assert!((weights::chi_square(&[70, 30], &[0.8, 0.2]) - 6.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check_split_tolerance() {
+        assert!(weights::check_split(&[78, 22], &[0.8, 0.2], 0.05).is_ok());
+        assert!(weights::check_split(&[50, 50], &[1.0, 1.0], 0.0).is_ok());
+
+        let err = weights::check_split(&[60, 40], &[0.8, 0.2], 0.1).unwrap_err();
+        assert!(err.contains("output 0: 60 buffers, observed 0.600, expected 0.800 <--"));
+        assert!(err.contains("output 1: 40 buffers, observed 0.400, expected 0.200 <--"));
+
+        assert!(weights::check_split(&[0, 0], &[0.5, 0.5], 0.1).is_err());
+        assert!(weights::check_split(&[10], &[0.5, 0.5], 0.1).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 0.500")]
+    fn test_assert_split_panics_with_table() {
+        weights::assert_split(&[90, 10], &[0.5, 0.5], 0.1);
+    }
+
     #[test]
     fn test_create_dispatcher() {
         init_for_tests();
@@ -19,7 +19,9 @@ use std::ffi::CString;
 #[cfg(feature = "network-sim")]
 use ::network_sim::{
     get_connection_ips,
-    namespace::{cleanup_rist_test_links, create_shaped_veth_pair, ShapedVethConfig},
+    namespace::{
+        cleanup_rist_test_links, create_shaped_veth_pair, ShapedVethConfig, ShapedVethPairGuard,
+    },
     qdisc::QdiscManager,
     runtime::{apply_ingress_params, remove_ingress_params},
     types::NetworkParams,
@@ -95,7 +97,7 @@ async fn test_bonded_links_static_stress() {
         .collect();
 
     // Create shaped veth pairs (egress on TX, ingress mirror on RX)
-    let mut links: Vec<ShapedVethConfig> = Vec::with_capacity(profiles.len());
+    let mut links: Vec<ShapedVethPairGuard> = Vec::with_capacity(profiles.len());
     for p in &profiles {
         let cfg = ShapedVethConfig {
             tx_interface: p.veth_tx.clone(),
@@ -111,11 +113,14 @@ async fn test_bonded_links_static_stress() {
                 reorder_pct: 0.0,
                 duplicate_pct: 0.0,
                 loss_corr_pct: 0.0,
+                port_filter: Vec::new(),
             },
+            tx_ifindex: None,
+            rx_ifindex: None,
         };
-        create_shaped_veth_pair(&qdisc, &cfg).await.expect("veth");
+        let link = create_shaped_veth_pair(&qdisc, &cfg).await.expect("veth");
         let _ = apply_ingress_params(&qdisc, &cfg.rx_interface, &cfg.network_params).await;
-        links.push(cfg);
+        links.push(link);
     }
 
     // Sender pipeline: H.265 1080p30 at ~3000 kbps
@@ -371,7 +376,7 @@ async fn test_bonded_links_static_stress() {
     for cfg in &links {
         let _ = remove_ingress_params(&qdisc, &cfg.rx_interface).await;
     }
-    let _ = cleanup_rist_test_links(&qdisc, &links).await;
+    let _ = cleanup_rist_test_links(&qdisc, links).await;
 
     let observed_final = last_weights.expect("No dispatcher weights observed");
     assert_eq!(observed_final.len(), expected.len());
@@ -551,7 +556,7 @@ async fn test_bonded_links_static_stress_recorded() {
         .collect();
 
     // Create shaped veth pairs (egress on TX, ingress mirror on RX)
-    let mut links: Vec<ShapedVethConfig> = Vec::with_capacity(profiles.len());
+    let mut links: Vec<ShapedVethPairGuard> = Vec::with_capacity(profiles.len());
     for p in &profiles {
         let cfg = ShapedVethConfig {
             tx_interface: p.veth_tx.clone(),
@@ -567,11 +572,14 @@ async fn test_bonded_links_static_stress_recorded() {
                 reorder_pct: 0.0,
                 duplicate_pct: 0.0,
                 loss_corr_pct: 0.0,
+                port_filter: Vec::new(),
             },
+            tx_ifindex: None,
+            rx_ifindex: None,
         };
-        create_shaped_veth_pair(&qdisc, &cfg).await.expect("veth");
+        let link = create_shaped_veth_pair(&qdisc, &cfg).await.expect("veth");
         let _ = apply_ingress_params(&qdisc, &cfg.rx_interface, &cfg.network_params).await;
-        links.push(cfg);
+        links.push(link);
     }
 
     // Sender pipeline: H.265 1080p30 at ~3000 kbps, tuned for recording scenario
@@ -921,7 +929,7 @@ async fn test_bonded_links_static_stress_recorded() {
     for cfg in &links {
         let _ = remove_ingress_params(&qdisc, &cfg.rx_interface).await;
     }
-    let _ = cleanup_rist_test_links(&qdisc, &links).await;
+    let _ = cleanup_rist_test_links(&qdisc, links).await;
 
     let observed_final = last_weights.expect("No dispatcher weights observed");
     assert_eq!(observed_final.len(), expected.len());
@@ -0,0 +1,131 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use gstristelements::CounterSink;
+use std::time::Duration;
+
+#[test]
+fn test_counter_sink_flow_return_switches_at_runtime() {
+    init_for_tests();
+
+    let sink = create_counter_sink();
+    let src = gst::Pad::builder(gst::PadDirection::Src)
+        .name("test_src")
+        .build();
+    src.link(&sink.static_pad("sink").unwrap()).unwrap();
+    src.set_active(true).unwrap();
+    sink.set_state(gst::State::Playing).unwrap();
+    src.push_event(gst::event::StreamStart::new("counter-sink-failover"));
+    src.push_event(gst::event::Segment::new(&gst::FormattedSegment::<
+        gst::ClockTime,
+    >::new()));
+    let push = || src.push(gst::Buffer::with_size(10).unwrap());
+
+    assert_eq!(sink.property::<String>("flow-return"), "ok");
+    assert!(push().is_ok());
+
+    sink.set_property("flow-return", "flushing");
+    assert_eq!(push(), Err(gst::FlowError::Flushing));
+    sink.set_property("flow-return", "not-linked");
+    assert_eq!(push(), Err(gst::FlowError::NotLinked));
+    sink.set_property("flow-return", "ok");
+    assert!(push().is_ok());
+
+    // fail-after defaults to error once the sink has accepted N buffers
+    sink.set_property("fail-after", 3u64);
+    assert!(push().is_ok());
+    assert_eq!(push(), Err(gst::FlowError::Error));
+    assert_eq!(sink.property::<u64>("count"), 3);
+
+    sink.set_state(gst::State::Null).unwrap();
+}
+
+/// Two-output dispatcher whose source produces `buffers` buffers
+fn failover_pipeline(buffers: i32) -> (gst::Pipeline, Vec<CounterSink>) {
+    let (pipeline, _dispatcher, counters) =
+        pipelines::dispatcher_with_counters(2, Some(&[0.5, 0.5]));
+    let source = pipeline
+        .iterate_elements()
+        .into_iter()
+        .flatten()
+        .find(|e| e.factory().is_some_and(|f| f.name() == "audiotestsrc"))
+        .expect("test source");
+    source.set_property("num-buffers", buffers);
+    (pipeline, counters)
+}
+
+/// Play to EOS and return the text of every warning posted on the bus
+fn run_collecting_warnings(pipeline: &gst::Pipeline) -> Vec<String> {
+    let bus = pipeline.bus().unwrap();
+    pipeline.set_state(gst::State::Playing).unwrap();
+    let mut warnings = Vec::new();
+    let deadline = std::time::Instant::now() + Duration::from_secs(10);
+    loop {
+        assert!(std::time::Instant::now() < deadline, "timed out");
+        let Some(msg) = bus.timed_pop_filtered(
+            gst::ClockTime::from_mseconds(50),
+            &[
+                gst::MessageType::Eos,
+                gst::MessageType::Error,
+                gst::MessageType::Warning,
+            ],
+        ) else {
+            continue;
+        };
+        match msg.view() {
+            gst::MessageView::Warning(w) => warnings.push(w.error().to_string()),
+            gst::MessageView::Error(err) => panic!("Pipeline error: {}", err.error()),
+            _ => break,
+        }
+    }
+    pipeline.set_state(gst::State::Null).unwrap();
+    warnings
+}
+
+#[test]
+fn test_dispatcher_fails_over_from_erroring_sink() {
+    init_for_tests();
+
+    let (pipeline, counters) = failover_pipeline(400);
+    counters[0].set_property("fail-after", 100u64);
+    counters[0].set_property("flow-return", "error");
+    let failing_pad = counters[0]
+        .static_pad("sink")
+        .and_then(|p| p.peer())
+        .expect("failing counter linked")
+        .name();
+
+    let warnings = run_collecting_warnings(&pipeline);
+
+    let counts = weights::collect_counts(&counters);
+    println!("Failing sink: {}, healthy sink: {}", counts[0], counts[1]);
+    println!("Warnings: {:?}", warnings);
+
+    // Everything after the failure lands on the healthy sink
+    assert_eq!(counts[0], 100);
+    assert_eq!(counts[0] + counts[1], 400);
+
+    // One warning for the failing output, not one per refused buffer
+    let matching: Vec<_> = warnings
+        .iter()
+        .filter(|w| w.contains(failing_pad.as_str()))
+        .collect();
+    assert_eq!(matching.len(), 1, "warnings: {:?}", warnings);
+}
+
+/// Flushing outputs are routine (seeks, teardown) and must not warn
+#[test]
+fn test_dispatcher_does_not_warn_on_flushing_sink() {
+    init_for_tests();
+
+    let (pipeline, counters) = failover_pipeline(200);
+    counters[0].set_property("fail-after", 50u64);
+    counters[0].set_property("flow-return", "flushing");
+
+    let warnings = run_collecting_warnings(&pipeline);
+
+    let counts = weights::collect_counts(&counters);
+    assert_eq!(counts[0], 50);
+    assert_eq!(counts[0] + counts[1], 200);
+    assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+}
@@ -0,0 +1,92 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use std::time::Duration;
+
+/// Source pad linked straight into a counter_sink so tests control timing
+fn linked_src(sink: &gst::Element) -> gst::Pad {
+    let src = gst::Pad::builder(gst::PadDirection::Src)
+        .name("test_src")
+        .build();
+    src.link(&sink.static_pad("sink").unwrap()).unwrap();
+    src.set_active(true).unwrap();
+    sink.set_state(gst::State::Playing).unwrap();
+    src.push_event(gst::event::StreamStart::new("counter-sink-timing"));
+    src.push_event(gst::event::Segment::new(&gst::FormattedSegment::<
+        gst::ClockTime,
+    >::new()));
+    src
+}
+
+fn push(src: &gst::Pad, size: usize) {
+    src.push(gst::Buffer::with_size(size).unwrap()).unwrap();
+}
+
+fn arrival_times(sink: &gst::Element) -> Vec<u64> {
+    let arr = sink.emit_by_name::<gst::Array>("get-arrival-times", &[]);
+    arr.iter().map(|v| v.get::<u64>().unwrap()).collect()
+}
+
+#[test]
+fn test_counter_sink_inter_arrival_stats() {
+    init_for_tests();
+
+    let sink = create_counter_sink();
+    let src = linked_src(&sink);
+
+    // Three 20 ms gaps and one 100 ms stall
+    push(&src, 100);
+    for _ in 0..3 {
+        std::thread::sleep(Duration::from_millis(20));
+        push(&src, 100);
+    }
+    std::thread::sleep(Duration::from_millis(100));
+    push(&src, 200);
+
+    assert_eq!(sink.property::<u64>("count"), 5);
+    assert_eq!(sink.property::<u64>("bytes"), 600);
+
+    let max: f64 = sink.property("max-inter-arrival-ms");
+    assert!((100.0..150.0).contains(&max), "max gap {}", max);
+    let avg: f64 = sink.property("avg-inter-arrival-ms");
+    assert!((40.0..60.0).contains(&avg), "avg gap {}", avg);
+    let jitter: f64 = sink.property("jitter-ms");
+    assert!(jitter > 20.0, "jitter {}", jitter);
+
+    let times = arrival_times(&sink);
+    assert_eq!(times.len(), 5);
+    assert_eq!(times[0], 0);
+    assert!(times.windows(2).all(|w| w[0] <= w[1]));
+    assert!(times[4] - times[3] >= 100_000);
+
+    sink.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_counter_sink_steady_flow_has_low_jitter() {
+    init_for_tests();
+
+    let sink = create_counter_sink();
+    let src = linked_src(&sink);
+
+    // No gaps yet
+    assert_eq!(sink.property::<f64>("max-inter-arrival-ms"), 0.0);
+    assert!(arrival_times(&sink).is_empty());
+
+    for _ in 0..10 {
+        push(&src, 50);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let avg: f64 = sink.property("avg-inter-arrival-ms");
+    assert!((9.0..25.0).contains(&avg), "avg gap {}", avg);
+    let jitter: f64 = sink.property("jitter-ms");
+    assert!(
+        jitter < avg,
+        "jitter {} should be below avg gap {}",
+        jitter,
+        avg
+    );
+
+    sink.set_state(gst::State::Null).unwrap();
+}
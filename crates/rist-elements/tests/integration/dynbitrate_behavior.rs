@@ -11,45 +11,30 @@ use gstreamer as gst;
 use serial_test::serial;
 
 // Test helpers
+#[cfg(feature = "test-plugin")]
+use gstristelements::testing::pipelines;
 use gstristelements::testing::{get_property, init_for_tests, wait_for_state_change};
 
 // Test harness types (available under the default `test-plugin` feature)
 #[cfg(feature = "test-plugin")]
-use gstristelements::RistStatsMock;
+use gstristelements::{DynBitrate, RistStatsMock};
 
 // Convenience to create a basic pipeline with encoder_stub -> dynbitrate -> fakesink
 #[cfg(feature = "test-plugin")]
-fn make_pipeline_with_dynbitrate() -> (gst::Pipeline, gst::Element, gst::Element, RistStatsMock) {
+fn make_pipeline_with_dynbitrate() -> (gst::Pipeline, gst::Element, DynBitrate, RistStatsMock) {
     init_for_tests();
 
-    // Elements
     let encoder = gstristelements::testing::create_encoder_stub(Some(5000)); // 5000 kbps start
-    let dynbitrate = gstristelements::testing::create_dynbitrate();
-    let sink = gstristelements::testing::create_fake_sink();
-
-    // Use RIST stats mock as the RIST element dynbitrate reads from
-    let rist_elem = gstristelements::testing::create_riststats_mock(None, None);
-    let rist_mock = rist_elem
-        .clone()
-        .downcast::<RistStatsMock>()
-        .expect("riststats_mock type");
+    let rist_mock = gstristelements::testing::create_mock_stats(2);
+    let (pipeline, dynbitrate) = pipelines::dynbitrate_chain(&encoder, None, &rist_mock);
 
     // Configure dynbitrate
-    dynbitrate.set_property("encoder", &encoder);
-    dynbitrate.set_property("rist", &rist_elem);
     dynbitrate.set_property("min-kbps", 1000u32);
     dynbitrate.set_property("max-kbps", 8000u32);
     dynbitrate.set_property("step-kbps", 500u32);
     dynbitrate.set_property("target-loss-pct", 1.0f64); // 1% target
     dynbitrate.set_property("min-rtx-rtt-ms", 40u64);
 
-    // Build pipeline
-    let pipeline = gst::Pipeline::new();
-    pipeline
-        .add_many([&encoder, &dynbitrate, &sink])
-        .expect("add elements");
-    gst::Element::link_many([&encoder, &dynbitrate, &sink]).expect("link chain");
-
     (pipeline, encoder, dynbitrate, rist_mock)
 }
 
@@ -339,26 +339,12 @@ async fn test_buffer_flow_integrity() -> Result<(), Box<dyn std::error::Error>>
 
     let (pipeline, _src, dispatcher, _sink) = create_test_pipeline();
 
-    // Set up buffer counting
-    let buffer_count = Arc::new(Mutex::new(0u64));
-    let byte_count = Arc::new(Mutex::new(0u64));
-
-    // Request src pad and link to sink
+    // Count buffers and bytes in a counter_sink rather than with a pad probe
+    let counter = testing::create_counter_sink();
+    pipeline.remove(&_sink)?;
+    pipeline.add(&counter)?;
     let src_pad = dispatcher.request_pad_simple("src_%u").unwrap();
-    let sink_pad = _sink.static_pad("sink").unwrap();
-    src_pad.link(&sink_pad).unwrap();
-
-    // Add probe to count buffers
-    let buffer_count_clone = buffer_count.clone();
-    let byte_count_clone = byte_count.clone();
-
-    src_pad.add_probe(gst::PadProbeType::BUFFER, move |_pad, info| {
-        if let Some(gst::PadProbeData::Buffer(ref buffer)) = info.data {
-            *buffer_count_clone.lock().unwrap() += 1;
-            *byte_count_clone.lock().unwrap() += buffer.size() as u64;
-        }
-        gst::PadProbeReturn::Ok
-    });
+    src_pad.link(&counter.static_pad("sink").unwrap())?;
 
     // Configure source for limited buffers
     _src.set_property("num-buffers", 100i32);
@@ -372,7 +358,7 @@ async fn test_buffer_flow_integrity() -> Result<(), Box<dyn std::error::Error>>
             tokio::time::sleep(Duration::from_millis(100)).await;
 
             // Check if we've processed expected buffers
-            let count = *buffer_count.lock().unwrap();
+            let count: u64 = counter.property("count");
             if count >= 100 {
                 break;
             }
@@ -393,8 +379,8 @@ async fn test_buffer_flow_integrity() -> Result<(), Box<dyn std::error::Error>>
         "Buffer flow test should complete within timeout"
     );
 
-    let final_buffer_count = *buffer_count.lock().unwrap();
-    let final_byte_count = *byte_count.lock().unwrap();
+    let final_buffer_count: u64 = counter.property("count");
+    let final_byte_count: u64 = counter.property("bytes");
 
     println!(
         "Processed {} buffers, {} bytes",
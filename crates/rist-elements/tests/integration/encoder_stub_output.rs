@@ -0,0 +1,119 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Generating encoder_stub feeding a counter_sink, with a keyframe counter
+fn generating_pipeline(
+    bitrate: u32,
+    fps: u32,
+    keyframe_interval: u32,
+) -> (gst::Pipeline, gst::Element, gst::Element, Arc<AtomicU64>) {
+    let encoder = create_encoder_stub(Some(bitrate));
+    encoder.set_property("generate", true);
+    encoder.set_property("fps", fps);
+    encoder.set_property("keyframe-interval", keyframe_interval);
+    let sink = create_counter_sink();
+
+    let pipeline = gst::Pipeline::new();
+    pipeline.add_many([&encoder, &sink]).unwrap();
+    encoder.link(&sink).unwrap();
+
+    let keyframes = Arc::new(AtomicU64::new(0));
+    let counter = keyframes.clone();
+    encoder
+        .static_pad("src")
+        .unwrap()
+        .add_probe(gst::PadProbeType::BUFFER, move |_pad, info| {
+            if let Some(gst::PadProbeData::Buffer(ref buffer)) = info.data {
+                if !buffer.flags().contains(gst::BufferFlags::DELTA_UNIT) {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            gst::PadProbeReturn::Ok
+        });
+
+    (pipeline, encoder, sink, keyframes)
+}
+
+#[test]
+fn test_encoder_stub_output_tracks_bitrate() {
+    init_for_tests();
+
+    let (pipeline, encoder, sink, _) = generating_pipeline(800, 50, 30);
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    std::thread::sleep(Duration::from_millis(1000));
+    let low_bytes: u64 = sink.property("bytes");
+
+    encoder.set_property("bitrate", 3200u32);
+    std::thread::sleep(Duration::from_millis(1000));
+    let high_bytes = sink.property::<u64>("bytes") - low_bytes;
+
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    // 800 kbps = 100 kB/s, 3200 kbps = 400 kB/s
+    assert!(
+        (70_000..130_000).contains(&low_bytes),
+        "low bitrate produced {} bytes",
+        low_bytes
+    );
+    let ratio = high_bytes as f64 / low_bytes as f64;
+    assert!(
+        (3.0..5.0).contains(&ratio),
+        "byte rate should scale with bitrate, ratio {:.2}",
+        ratio
+    );
+}
+
+#[test]
+fn test_encoder_stub_keyframe_cadence() {
+    init_for_tests();
+
+    let (pipeline, _encoder, sink, keyframes) = generating_pipeline(1000, 100, 10);
+    pipeline.set_state(gst::State::Playing).unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    let count: u64 = sink.property("count");
+    let expected = count.div_ceil(10);
+    assert_eq!(keyframes.load(Ordering::SeqCst), expected);
+}
+
+#[test]
+fn test_encoder_stub_force_key_unit() {
+    init_for_tests();
+
+    // Keyframe interval far longer than the test so only forced ones appear
+    let (pipeline, encoder, _sink, keyframes) = generating_pipeline(1000, 50, 100_000);
+    pipeline.set_state(gst::State::Playing).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(keyframes.load(Ordering::SeqCst), 1, "only the first buffer");
+
+    encoder.emit_by_name::<()>("force-key-unit", &[]);
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        keyframes.load(Ordering::SeqCst),
+        2,
+        "signal forces a keyframe"
+    );
+
+    // dynbitrate sends a GstForceKeyUnit custom event to the sink pad
+    let event = gst::event::CustomDownstream::new(
+        gst::Structure::builder("GstForceKeyUnit")
+            .field("all-headers", true)
+            .field("count", 1u32)
+            .build(),
+    );
+    assert!(encoder.static_pad("sink").unwrap().send_event(event));
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        keyframes.load(Ordering::SeqCst),
+        3,
+        "event forces a keyframe"
+    );
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
@@ -4,12 +4,15 @@
 //! and cross-component system behavior.
 
 mod backpressure_simulation;
+mod counter_sink_failover;
+mod counter_sink_timing;
 mod cross_element_integration;
 mod dynbitrate_behavior;
 mod dynbitrate_keyframes;
 mod dynbitrate_stats_edge_cases;
 mod edge_case_coverage;
 mod element_integration;
+mod encoder_stub_output;
 mod error_misuse_scenarios;
 mod error_recovery;
 mod extended_rebalancing;
@@ -25,5 +28,8 @@ mod pad_removal_simple;
 mod performance_benchmarks;
 mod pipeline_tests;
 mod property_debug;
+mod ristsink_mock_weights;
 mod runtime_updates;
+mod stats_mock_modes;
+mod stats_timeline;
 mod thread_safety;
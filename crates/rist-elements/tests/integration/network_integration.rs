@@ -32,6 +32,48 @@ mod network_sim_tests {
         let result = testing::network_sim::apply_good_conditions(interface).await;
         println!("Apply good conditions result: {:?}", result);
     }
+
+    #[tokio::test]
+    async fn test_localhost_latency_with_fallback() {
+        use std::time::{Duration, Instant};
+        use tokio::net::UdpSocket;
+
+        let echo = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let upstream = echo.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            while let Ok((n, from)) = echo.recv_from(&mut buf).await {
+                let _ = echo.send_to(&buf[..n], from).await;
+            }
+        });
+
+        let params = NetworkParams {
+            delay_ms: 20,
+            ..Default::default()
+        };
+        let impairment = testing::network_sim::impair_localhost(params, upstream)
+            .await
+            .expect("localhost impairment");
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let start = Instant::now();
+        client
+            .send_to(b"probe", ("127.0.0.1", impairment.port))
+            .await
+            .unwrap();
+        let mut buf = [0u8; 16];
+        tokio::time::timeout(Duration::from_secs(2), client.recv(&mut buf))
+            .await
+            .expect("echo reply")
+            .unwrap();
+        let rtt = start.elapsed();
+        impairment.clear();
+
+        assert!(
+            rtt >= Duration::from_millis(40),
+            "RTT {:?} should include 2 x 20 ms",
+            rtt
+        );
+    }
 }
 
 mod basic_tests {
@@ -312,3 +312,122 @@ fn test_mixed_network_conditions() {
         "Should maintain valid final state"
     );
 }
+
+/// Both RTP sequence elements start counting afresh when restarted
+#[test]
+fn test_rtp_seq_elements_reset_on_restart() {
+    init_for_tests();
+
+    const PACKETS: u64 = 200;
+
+    let pipeline = gst::Pipeline::new();
+    let src = create_rtp_seq_src(2000, PACKETS);
+    let check = create_rtp_seq_check();
+    pipeline.add_many([&src, &check]).unwrap();
+    src.link(&check).unwrap();
+
+    for run in 0..2 {
+        pipeline.set_state(gst::State::Playing).unwrap();
+        // Pushes are synchronous, so the checker has seen every packet sent
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while src.property::<u64>("sent") < PACKETS && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        pipeline.set_state(gst::State::Null).unwrap();
+
+        assert_eq!(src.property::<u64>("sent"), PACKETS, "run {}", run);
+        assert_eq!(check.property::<u64>("received"), PACKETS, "run {}", run);
+        assert_eq!(check.property::<u64>("missing"), 0, "run {}", run);
+        assert_eq!(check.property::<u64>("duplicates"), 0, "run {}", run);
+    }
+}
+
+/// Sequence-numbered RTP through the dispatcher and an impaired localhost
+/// path: the receiver's missing count should match the configured loss
+#[cfg(feature = "network-sim")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rtp_sequence_loss_over_impaired_localhost() {
+    use ::network_sim::NetworkParams;
+    use gstristelements::testing::network_sim::impair_localhost;
+
+    init_for_tests();
+
+    const PACKETS: u64 = 1000;
+
+    // Reserve a receive port for udpsrc
+    let port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let params = NetworkParams {
+        loss_pct: 0.1,
+        ..Default::default()
+    };
+    let impairment = impair_localhost(params, ([127, 0, 0, 1], port).into())
+        .await
+        .expect("localhost impairment");
+
+    let recv_pipeline = gst::Pipeline::new();
+    let udpsrc = gst::ElementFactory::make("udpsrc")
+        .property("address", "127.0.0.1")
+        .property("port", port as i32)
+        .build()
+        .expect("udpsrc");
+    let check = create_rtp_seq_check();
+    recv_pipeline.add_many([&udpsrc, &check]).unwrap();
+    udpsrc.link(&check).unwrap();
+    recv_pipeline.set_state(gst::State::Playing).unwrap();
+
+    let send_pipeline = gst::Pipeline::new();
+    let src = create_rtp_seq_src(500, PACKETS);
+    let dispatcher = create_dispatcher_for_testing(Some(&[1.0]));
+    let udpsink = gst::ElementFactory::make("udpsink")
+        .property("host", "127.0.0.1")
+        .property("port", impairment.port as i32)
+        .property("sync", false)
+        .build()
+        .expect("udpsink");
+    send_pipeline
+        .add_many([&src, &dispatcher, &udpsink])
+        .unwrap();
+    src.link(&dispatcher).unwrap();
+    let dispatcher_src = dispatcher.request_pad_simple("src_%u").unwrap();
+    dispatcher_src
+        .link(&udpsink.static_pad("sink").unwrap())
+        .unwrap();
+    send_pipeline.set_state(gst::State::Playing).unwrap();
+
+    // 1000 packets at 500 pps, plus time for the tail to drain
+    let deadline = Instant::now() + Duration::from_secs(6);
+    while src.property::<u64>("sent") < PACKETS && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    send_pipeline.set_state(gst::State::Null).unwrap();
+    recv_pipeline.set_state(gst::State::Null).unwrap();
+    impairment.clear();
+
+    let received: u64 = check.property("received");
+    let missing: u64 = check.property("missing");
+    let duplicates: u64 = check.property("duplicates");
+    let out_of_order: u64 = check.property("out-of-order");
+    println!(
+        "received={} missing={} duplicates={} out-of-order={}",
+        received, missing, duplicates, out_of_order
+    );
+
+    assert_eq!(src.property::<u64>("sent"), PACKETS);
+    assert_eq!(duplicates, 0);
+    assert_eq!(out_of_order, 0);
+    // Losses after the last delivered packet are invisible to the checker
+    assert!(received + missing <= PACKETS);
+    let loss = 1.0 - received as f64 / PACKETS as f64;
+    assert!(
+        (0.05..0.15).contains(&loss),
+        "observed loss {:.3} should be near 0.1",
+        loss
+    );
+    assert!(missing as f64 >= loss * PACKETS as f64 * 0.8);
+}
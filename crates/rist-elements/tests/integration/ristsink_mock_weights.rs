@@ -0,0 +1,90 @@
+use gstreamer as gst;
+use gstreamer::glib;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use std::time::Duration;
+
+fn run_mainloop_ms(ms: u64) {
+    let ctx = glib::MainContext::default();
+    let _guard = ctx.acquire().expect("acquire main context");
+    let end = std::time::Instant::now() + Duration::from_millis(ms);
+    while std::time::Instant::now() < end {
+        while ctx.iteration(false) {}
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_ristsink_mock_session_stats() {
+    init_for_tests();
+
+    let mock = create_ristsink_mock();
+    let pad0 = mock.request_pad_simple("sink_%u").unwrap();
+    let pad1 = mock.request_pad_simple("sink_%u").unwrap();
+    assert_eq!(pad0.name(), "sink_0");
+    assert_eq!(pad1.name(), "sink_1");
+    mock.set_session_quality(1, 0.5, 80);
+
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(stats.name(), "rist/x-sender-stats");
+    let sessions = stats.get::<glib::ValueArray>("session-stats").unwrap();
+    assert_eq!(sessions.len(), 2);
+    let sess1 = sessions[1].get::<gst::Structure>().unwrap();
+    assert_eq!(sess1.get::<i32>("session-id").unwrap(), 1);
+    assert_eq!(sess1.get::<u64>("sent-original-packets").unwrap(), 0);
+    assert_eq!(sess1.get::<u64>("round-trip-time").unwrap(), 80_000_000);
+
+    mock.release_request_pad(&pad0);
+    let stats: gst::Structure = mock.property("stats");
+    let sessions = stats.get::<glib::ValueArray>("session-stats").unwrap();
+    assert_eq!(sessions.len(), 1);
+}
+
+/// The dispatcher polls the mock's real per-pad counts and shifts weight
+/// toward the session reporting fewer retransmissions and lower RTT
+#[test]
+fn test_dispatcher_weights_follow_ristsink_mock() {
+    init_for_tests();
+
+    let pipeline = gst::Pipeline::new();
+    let src = create_rtp_seq_src(1000, 0);
+    let dispatcher = create_dispatcher_for_testing(Some(&[1.0, 1.0]));
+    dispatcher.set_property("strategy", "ewma");
+    dispatcher.set_property("auto-balance", true);
+    dispatcher.set_property("rebalance-interval-ms", 200u64);
+    let mock = create_ristsink_mock();
+
+    pipeline
+        .add_many([&src, &dispatcher, mock.upcast_ref()])
+        .unwrap();
+    src.link(&dispatcher).unwrap();
+    for _ in 0..2 {
+        let dispatcher_src = dispatcher.request_pad_simple("src_%u").unwrap();
+        let session = mock.request_pad_simple("sink_%u").unwrap();
+        dispatcher_src.link(&session).unwrap();
+    }
+    mock.set_session_quality(0, 0.0, 20);
+    mock.set_session_quality(1, 0.25, 200);
+    dispatcher.set_property("rist", &mock);
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+    run_mainloop_ms(3000);
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    let weights: String = dispatcher.property("current-weights");
+    let weights: Vec<f64> = serde_json::from_str(&weights).expect("weights JSON");
+    println!(
+        "weights={:?} counts=[{}, {}]",
+        weights,
+        mock.session_count(0),
+        mock.session_count(1)
+    );
+
+    assert_eq!(weights.len(), 2);
+    assert!(
+        weights[0] > 0.6,
+        "healthy session should dominate, got {:?}",
+        weights
+    );
+    assert!(mock.session_count(0) > mock.session_count(1));
+}
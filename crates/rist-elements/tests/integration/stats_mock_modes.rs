@@ -0,0 +1,157 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use gstristelements::StatsMode;
+use serial_test::serial;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn field_names(s: &gst::Structure) -> Vec<String> {
+    let mut names: Vec<String> = s.fields().map(|f| f.to_string()).collect();
+    names.sort();
+    names
+}
+
+fn expected_fields(per_session: &[&str], sessions: usize) -> Vec<String> {
+    let mut names: Vec<String> = (0..sessions)
+        .flat_map(|i| {
+            per_session
+                .iter()
+                .map(move |f| format!("session-{}.{}", i, f))
+        })
+        .chain(per_session.iter().map(|f| f.to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_riststats_mock_sender_structure() {
+    init_for_tests();
+
+    let mock = create_mock_stats(2);
+    let mode: String = mock.property("mode");
+    assert_eq!(mode, "sender");
+
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(stats.name(), "rist/x-sender-stats");
+    assert_eq!(
+        field_names(&stats),
+        expected_fields(
+            &[
+                "sent-original-packets",
+                "sent-retransmitted-packets",
+                "round-trip-time"
+            ],
+            2
+        )
+    );
+}
+
+#[test]
+fn test_riststats_mock_receiver_structure() {
+    init_for_tests();
+
+    let mock = create_mock_stats(2);
+    mock.set_property("mode", "receiver");
+    mock.tick(&[1000, 500], &[0, 0], &[20, 40]);
+    mock.report_loss(0, 30, 25);
+    mock.report_loss(1, 10, 0);
+
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(stats.name(), "rist/x-receiver-stats");
+    assert_eq!(
+        field_names(&stats),
+        expected_fields(
+            &[
+                "received-packets",
+                "lost-packets",
+                "recovered-packets",
+                "round-trip-time"
+            ],
+            2
+        )
+    );
+
+    let get = |field: &str| stats.get::<u64>(field).unwrap();
+    assert_eq!(get("session-0.received-packets"), 995);
+    assert_eq!(get("session-0.lost-packets"), 30);
+    assert_eq!(get("session-0.recovered-packets"), 25);
+    assert_eq!(get("session-1.received-packets"), 490);
+    assert_eq!(get("lost-packets"), 40);
+    assert_eq!(stats.get::<f64>("round-trip-time").unwrap(), 20.0);
+}
+
+#[test]
+fn test_riststats_mock_mode_flip() {
+    init_for_tests();
+
+    let mock = create_mock_stats(1);
+    mock.set_mode(StatsMode::Receiver);
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(stats.name(), "rist/x-receiver-stats");
+
+    mock.set_mode(StatsMode::Sender);
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(stats.name(), "rist/x-sender-stats");
+
+    // Property changes notify "stats" like set_mode does; unknown modes are
+    // ignored without notifying
+    let notified = Arc::new(AtomicUsize::new(0));
+    let counter = notified.clone();
+    mock.connect_notify(Some("stats"), move |_, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    mock.set_property("mode", "receiver");
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+    mock.set_property("mode", "bogus");
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+    let mode: String = mock.property("mode");
+    assert_eq!(mode, "receiver");
+}
+
+fn run_mainloop_ms(ms: u64) {
+    let ctx = glib::MainContext::default();
+    let _guard = ctx.acquire().expect("acquire main context");
+    let end = std::time::Instant::now() + std::time::Duration::from_millis(ms);
+    while std::time::Instant::now() < end {
+        while ctx.iteration(false) {}
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
+
+/// dynbitrate reads sender counters only: it holds the bitrate while the
+/// attached mock reports receiver stats, and reacts once flipped back
+#[test]
+#[serial]
+fn test_dynbitrate_follows_mode_flip() {
+    init_for_tests();
+
+    let encoder = create_encoder_stub(Some(5000));
+    let mock = create_mock_stats(1);
+    let (pipeline, dynbitrate) = pipelines::dynbitrate_chain(&encoder, None, &mock);
+    dynbitrate.set_property("min-kbps", 1000u32);
+    dynbitrate.set_property("step-kbps", 500u32);
+    dynbitrate.set_property("target-loss-pct", 1.0f64);
+
+    // ~9% retransmissions and 10% loss: bad conditions in either view
+    mock.set_property("mode", "receiver");
+    mock.tick(&[2000], &[200], &[120]);
+    mock.report_loss(0, 200, 150);
+    wait_for_state_change(&pipeline, gst::State::Playing, 5).expect("playing");
+
+    run_mainloop_ms(1600);
+    let held: u32 = get_property(&encoder, "bitrate").unwrap();
+    assert_eq!(held, 5000, "receiver stats must not drive dynbitrate");
+
+    // At least one 750 ms tick, and at most two, which the 1200 ms rate
+    // limit collapses into a single step
+    mock.set_property("mode", "sender");
+    run_mainloop_ms(900);
+    let reduced: u32 = get_property(&encoder, "bitrate").unwrap();
+    assert_eq!(reduced, 4500, "sender stats should step the bitrate down");
+
+    let _ = pipeline.set_state(gst::State::Null);
+    drop(pipeline);
+    run_mainloop_ms(150);
+}
@@ -0,0 +1,81 @@
+use gstreamer as gst;
+use gstreamer::glib;
+use gstreamer::prelude::*;
+use gstristelements::testing::*;
+use gstristelements::SessionDelta;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn run_mainloop_ms(ms: u64) {
+    let ctx = glib::MainContext::default();
+    let _guard = ctx.acquire().expect("acquire main context");
+    let end = std::time::Instant::now() + Duration::from_millis(ms);
+    while std::time::Instant::now() < end {
+        while ctx.iteration(false) {}
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn delta(original: u64, retrans: u64, rtt_ms: Option<u64>) -> SessionDelta {
+    SessionDelta {
+        original,
+        retrans,
+        rtt_ms,
+    }
+}
+
+/// A scripted timeline notifies once per step and accumulates counters
+#[test]
+fn test_riststats_mock_timeline_steps() {
+    init_for_tests();
+
+    let mock = create_mock_stats(2);
+    let notifies = Arc::new(AtomicUsize::new(0));
+    let counter = notifies.clone();
+    mock.connect_notify(Some("stats"), move |_, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    mock.set_timeline(vec![
+        (20, vec![delta(100, 1, Some(20)), delta(50, 0, Some(40))]),
+        (40, vec![delta(100, 2, None), delta(50, 5, Some(80))]),
+        (60, vec![delta(100, 0, Some(25)), delta(50, 1, None)]),
+    ]);
+    mock.start_timeline();
+    run_mainloop_ms(300);
+
+    assert_eq!(notifies.load(Ordering::SeqCst), 3);
+
+    let stats: gst::Structure = mock.property("stats");
+    let get = |field: &str| stats.get::<u64>(field).unwrap();
+    assert_eq!(get("session-0.sent-original-packets"), 300);
+    assert_eq!(get("session-0.sent-retransmitted-packets"), 3);
+    assert_eq!(get("session-1.sent-original-packets"), 150);
+    assert_eq!(get("session-1.sent-retransmitted-packets"), 6);
+    assert_eq!(stats.get::<f64>("session-0.round-trip-time").unwrap(), 25.0);
+    assert_eq!(stats.get::<f64>("session-1.round-trip-time").unwrap(), 80.0);
+    assert_eq!(get("sent-original-packets"), 450);
+}
+
+/// Stopping a timeline drops the remaining steps
+#[test]
+fn test_riststats_mock_timeline_stop() {
+    init_for_tests();
+
+    let mock = create_mock_stats(1);
+    mock.set_timeline(vec![
+        (10, vec![delta(10, 0, None)]),
+        (150, vec![delta(10, 0, None)]),
+    ]);
+    mock.start_timeline();
+    run_mainloop_ms(60);
+    mock.stop_timeline();
+    run_mainloop_ms(200);
+
+    let stats: gst::Structure = mock.property("stats");
+    assert_eq!(
+        stats.get::<u64>("session-0.sent-original-packets").unwrap(),
+        10
+    );
+}
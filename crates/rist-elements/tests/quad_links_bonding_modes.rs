@@ -158,6 +158,7 @@ async fn quad_links_broadcast_and_roundrobin() {
         duplicate_pct: 0.0,
         reorder_pct: 0.0,
         rate_kbps: 9000,
+        port_filter: Vec::new(),
     };
 
     // Allocate names/ips/ports
@@ -177,6 +177,7 @@ async fn single_link_point_to_point_rist() {
         duplicate_pct: 0.0,
         reorder_pct: 0.0,
         rate_kbps: 5000,
+        port_filter: Vec::new(),
     };
 
     let qdisc = Arc::new(QdiscManager::new());
@@ -169,71 +169,29 @@ fn test_weighted_distribution_pipeline() {
 
     println!("=== Weighted Distribution Pipeline Test ===");
 
-    // Create elements
-    let source = create_test_source();
-    // Configure dispatcher for pure SWRR behavior to make distribution deterministic
-    let dispatcher = create_dispatcher(Some(&[0.8, 0.2])); // Heavily favor first output
-    dispatcher.set_property("auto-balance", false);
-    dispatcher.set_property("min-hold-ms", 0u64);
-    dispatcher.set_property("switch-threshold", 1.0f64);
-    dispatcher.set_property("health-warmup-ms", 0u64);
-    let counter1 = create_counter_sink();
-    let counter2 = create_counter_sink();
-
-    // Create pipeline
-    let pipeline = gst::Pipeline::new();
-    pipeline
-        .add_many([&source, &dispatcher, &counter1, &counter2])
-        .expect("Failed to add elements to pipeline");
-
-    // Request src pads from dispatcher
-    let src_0 = dispatcher.request_pad_simple("src_%u").unwrap();
-    let src_1 = dispatcher.request_pad_simple("src_%u").unwrap();
-
-    // Link elements
-    source
-        .link(&dispatcher)
-        .expect("Failed to link source to dispatcher");
-    src_0
-        .link(&counter1.static_pad("sink").unwrap())
-        .expect("Failed to link dispatcher src_0");
-    src_1
-        .link(&counter2.static_pad("sink").unwrap())
-        .expect("Failed to link dispatcher src_1");
-
-    // Run the pipeline a bit longer to allow distribution to manifest
-    run_pipeline_for_duration(&pipeline, 2).expect("Pipeline run failed");
-
-    // Check distribution
-    let count1: u64 = get_property(&counter1, "count").unwrap();
-    let count2: u64 = get_property(&counter2, "count").unwrap();
-
-    println!("Counter 1: {} buffers (weight 0.8)", count1);
-    println!("Counter 2: {} buffers (weight 0.2)", count2);
+    // Testing dispatcher settings give pure SWRR behavior, making distribution deterministic
+    let (pipeline, _dispatcher, counters) =
+        pipelines::dispatcher_with_counters(2, Some(&[0.8, 0.2])); // Heavily favor first output
+
+    // Run for the source's full 100 buffers rather than wall-clock time
+    let counts = weights::run_for_buffers(
+        &pipeline,
+        &counters,
+        100,
+        std::time::Duration::from_secs(10),
+    )
+    .expect("Pipeline run failed");
+
+    println!("Counter 1: {} buffers (weight 0.8)", counts[0]);
+    println!("Counter 2: {} buffers (weight 0.2)", counts[1]);
 
     // With 0.8/0.2 weights, we should see buffers on both outputs, dominated by counter1
-    assert!(count1 > 0, "Counter 1 should receive buffers");
     assert!(
-        count2 > 0,
+        counts[1] > 0,
         "Counter 2 should receive some buffers for 0.2 weight"
     );
-
-    let total = count1 + count2;
-    if total > 0 {
-        let ratio1 = count1 as f64 / total as f64;
-        let ratio2 = count2 as f64 / total as f64;
-        // Allow generous tolerance due to discrete SWRR and startup effects
-        assert!(
-            ratio1 > 0.55,
-            "Expected majority on path1 (~0.8), got {:.2}",
-            ratio1
-        );
-        assert!(
-            ratio2 < 0.45,
-            "Expected minority on path2 (~0.2), got {:.2}",
-            ratio2
-        );
-    }
+    // Allow generous tolerance due to discrete SWRR and startup effects
+    weights::assert_split(&counts, &[0.8, 0.2], 0.25);
 
     println!("✅ Weighted distribution pipeline test passed");
 }